@@ -19,7 +19,7 @@ enum VariableDefinitionContext {
 }
 
 #[derive(Logos, Debug, PartialEq, Clone)]
-#[logos(extras = SymbolTable)]
+#[logos(extras = &'e mut SymbolTable)]
 enum StringContext {
     #[token("'")]
     Quote,
@@ -33,7 +33,7 @@ enum StringContext {
 
 #[derive(Logos, Debug, PartialEq, Clone)]
 #[logos(skip r"\s+")]
-#[logos(extras = SymbolTable)]
+#[logos(extras = &'e mut SymbolTable)]
 enum StringInterpolationContext {
     #[regex(r"[[:alpha:]][[:alnum:]]*", get_variable_value)]
     Id(String /* value for the given id */),
@@ -62,9 +62,7 @@ fn variable_definition(lex: &mut Lexer<VariableDefinitionContext>) -> Option<(St
     let id = lex.slice().to_string();
     if let Some(Ok(VariableDefinitionContext::Equals)) = lex.next() {
         if let Some(Ok(VariableDefinitionContext::Quote)) = lex.next() {
-            let mut lex2 = lex.clone().morph::<StringContext>();
-            let value = get_string_content(&mut lex2);
-            *lex = lex2.morph();
+            let value = lex.sublex(|symbols| symbols, get_string_content);
             lex.extras.insert(id.clone(), value.clone());
             return Some((id, value));
         }
@@ -75,24 +73,26 @@ fn variable_definition(lex: &mut Lexer<VariableDefinitionContext>) -> Option<(St
 
 /* ANCHOR: evaluate_interpolation */
 fn evaluate_interpolation(lex: &mut Lexer<StringContext>) -> Option<String> {
-    let mut lex2 = lex.clone().morph::<StringInterpolationContext>();
-    let mut interpolation = String::new();
-    while let Some(result) = lex2.next() {
-        match result {
-            Ok(token) => match token {
-                StringInterpolationContext::Id(value) => interpolation.push_str(&value),
-                StringInterpolationContext::Quote => {
-                    *lex = lex2.morph();
-                    interpolation.push_str(&get_string_content(lex));
-                    lex2 = lex.clone().morph();
+    lex.sublex(
+        |symbols| &mut **symbols,
+        |lex: &mut Lexer<StringInterpolationContext>| {
+            let mut interpolation = String::new();
+            while let Some(result) = lex.next() {
+                match result {
+                    Ok(token) => match token {
+                        StringInterpolationContext::Id(value) => interpolation.push_str(&value),
+                        StringInterpolationContext::Quote => {
+                            let value = lex.sublex(|symbols| &mut **symbols, get_string_content);
+                            interpolation.push_str(&value);
+                        }
+                        StringInterpolationContext::InterpolationEnd => break,
+                    },
+                    Err(()) => panic!("Interpolation error"),
                 }
-                StringInterpolationContext::InterpolationEnd => break,
-            },
-            Err(()) => panic!("Interpolation error"),
-        }
-    }
-    *lex = lex2.morph();
-    Some(interpolation)
+            }
+            Some(interpolation)
+        },
+    )
 }
 /* ANCHOR_END: evaluate_interpolation */
 
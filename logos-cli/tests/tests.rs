@@ -21,7 +21,9 @@ fn gen_to_file(format: bool) -> NamedTempFile {
 }
 
 fn get_features_label() -> &'static str {
-    if cfg!(feature = "state_machine_codegen") {
+    if cfg!(feature = "table_codegen") {
+        "table"
+    } else if cfg!(feature = "state_machine_codegen") {
         "state_machine"
     } else {
         "tailcall"
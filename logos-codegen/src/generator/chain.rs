@@ -0,0 +1,44 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+
+use crate::graph::State;
+
+use super::Generator;
+
+impl Generator<'_> {
+    /// Returns a comparison of all the bytes of a linear chain of states at once if `state`
+    /// starts one, otherwise returns an empty TokenStream. States inside a chain only get
+    /// a comparison if they can be entered from outside of it.
+    ///
+    /// If the next bytes match the whole chain, the states in between are skipped. Otherwise,
+    /// including when there are not enough bytes left, the fork of `state` is used as usual,
+    /// stepping through the chain one byte at a time.
+    ///
+    /// Chains ending in a state limited by `max_len` aren't compared at once, so that the
+    /// length limit is checked after every byte.
+    pub fn maybe_impl_chain(&self, state: State) -> TokenStream {
+        if !self.chain_heads[state.index()] {
+            return TokenStream::new();
+        }
+        let Some((bytes, end)) = self.graph.linear_chain(state) else {
+            return TokenStream::new();
+        };
+        if self.max_token_len(end).is_some() {
+            return TokenStream::new();
+        }
+
+        let len = Literal::usize_unsuffixed(bytes.len());
+        let literal = Literal::byte_string(&bytes);
+        let read = self.read_method();
+        let transition = self.state_transition(end);
+
+        quote! {
+            if let _Option::Some(chunk) = lex.#read::<&[::core::primitive::u8; #len]>(offset) {
+                if chunk == #literal {
+                    offset += #len;
+                    #transition
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::leaf::VariantKind;
+
+use super::Generator;
+
+impl Generator<'_> {
+    /// Generates the `lex_const` function of `#[logos(const_fn)]`, which lexes a single token
+    /// in const contexts. It walks the same tables as [`Self::generate_table`], without going
+    /// through `Lexer`, whose methods can't be called in const functions.
+    ///
+    /// Every leaf must be a unit variant or a skip, without callbacks.
+    pub fn generate_const_fn(&self, utf8_mode: bool) -> TokenStream {
+        let name = self.name;
+        let this = self.this;
+        let lt_bounds = self.lifetime_bounds;
+
+        let leaf_enum = self.leaf_enum();
+        let definitions = self.table_definitions();
+
+        let (source, bytes) = match utf8_mode {
+            true => (quote!(::core::primitive::str), quote!(source.as_bytes())),
+            false => (quote!([::core::primitive::u8]), quote!(source)),
+        };
+
+        let leaf_actions = self
+            .graph
+            .leaves()
+            .iter()
+            .zip(&self.leaf_idents)
+            .map(|(leaf, [_snake, pascal])| {
+                let action = match &leaf.kind {
+                    VariantKind::Unit(ident) => quote! {
+                        return _Option::Some((_Result::Ok(#name::#ident), start..end));
+                    },
+                    VariantKind::Skip => quote! {
+                        start = end;
+                        continue 'lex;
+                    },
+                    VariantKind::Value(..) => unreachable!("Checked when parsing"),
+                };
+                let max_len = leaf.max_len.map(|max_len| {
+                    quote! {
+                        if end - start > #max_len {
+                            return _Option::Some((_Result::Err(()), start..end));
+                        }
+                    }
+                });
+                quote!(_Option::Some(LogosLeaf::#pascal) => { #max_len #action })
+            })
+            .collect::<Vec<_>>();
+
+        // This is needed in Rust versions before 1.83 where you need to match Some
+        // for Option<Enum> even if the enum has no variants.
+        let default_case = self.graph.leaves().is_empty().then(|| {
+            quote! {
+                _Option::Some(_) => ::core::unreachable!("There are no matchable tokens"),
+            }
+        });
+
+        // Errors end at the next char boundary, like `Source::find_boundary`
+        let find_boundary = utf8_mode.then(|| {
+            quote! {
+                while end < bytes.len() && (bytes[end] as ::core::primitive::i8) < -0x40 {
+                    end += 1;
+                }
+            }
+        });
+
+        // Tokens growing past their length limit are errors ending at the first byte past it
+        let max_len = (!self.max_token_lens.is_empty()).then(|| {
+            quote! {
+                if offset < bytes.len() && offset - start > MAX_LENS[state as ::core::primitive::usize] {
+                    let mut end = offset;
+                    #find_boundary
+                    return _Option::Some((_Result::Err(()), start..end));
+                }
+            }
+        });
+
+        quote! {
+            impl #lt_bounds #this {
+                /// Lexes the token starting at byte `offset` of `source`, in a const context.
+                ///
+                /// Returns the token and its span, skipping the input matched by `skip` patterns,
+                /// or `None` at the end of the input. Input that doesn't match any token is
+                /// returned as an `Err(())`, like the default error of the lexer, as are tokens
+                /// over their length limit.
+                #[allow(unused)]
+                pub const fn lex_const(
+                    source: &#source,
+                    offset: ::core::primitive::usize,
+                ) -> ::core::option::Option<(
+                    ::core::result::Result<Self, ()>,
+                    ::core::ops::Range<::core::primitive::usize>,
+                )> {
+                    use ::core::option::Option as _Option;
+                    use ::core::result::Result as _Result;
+
+                    #leaf_enum
+                    #definitions
+
+                    let bytes: &[::core::primitive::u8] = #bytes;
+                    let mut start = offset;
+                    'lex: loop {
+                        let mut state = ROOT;
+                        let mut offset = start;
+                        let mut end = start;
+                        let mut context: _Option<LogosLeaf> = _Option::None;
+                        loop {
+                            let info = STATES[state as ::core::primitive::usize];
+                            if let _Option::Some(leaf) = info.context {
+                                // The 1 comes from the 1 byte delayed match behavior
+                                // of the regex-automata crate.
+                                end = if info.early { offset } else { offset - 1 };
+                                context = _Option::Some(leaf);
+                            }
+
+                            let next = if offset < bytes.len() {
+                                let class = CLASSES[bytes[offset] as ::core::primitive::usize] as ::core::primitive::usize;
+                                TRANSITIONS[state as ::core::primitive::usize * CLASS_COUNT + class]
+                            } else if state == ROOT && offset == start {
+                                return _Option::None;
+                            } else {
+                                info.eoi
+                            };
+
+                            if next == DEAD {
+                                break;
+                            }
+                            #max_len
+                            offset += 1;
+                            state = next;
+                        }
+
+                        match context {
+                            #(#leaf_actions)*
+                            #default_case
+                            _Option::None => {
+                                let mut end = if offset > start { offset } else { start + 1 };
+                                #find_boundary
+                                return _Option::Some((_Result::Err(()), start..end));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use proc_macro2::TokenStream;
 use quote::quote;
+use syn::Ident;
 
+use crate::generator::byte_to_tokens;
 use crate::graph::{ByteClass, State};
 
 use super::Generator;
@@ -31,6 +33,10 @@ impl<'a> Generator<'a> {
     /// starting at `offset` until the given edge no longer applies. Offset will now be the first
     /// offset that transitions away from the current state.
     pub fn impl_fast_loop(&mut self, self_edge: &ByteClass) -> TokenStream {
+        if let Some(exits) = SwarExits::new(self_edge) {
+            return self.impl_swar_loop(exits);
+        }
+
         // Note: Unlike forks, we don't ever fall back to doing normal comparisons - A LUT is always
         // generated for the loop test. Since we read multiple times, its more likely we make back
         // the time spend possibly brining the lut back into cache. I think it might be better to
@@ -46,11 +52,112 @@ impl<'a> Generator<'a> {
             _fast_loop!(lex, loop_test, offset);
         }
     }
+
+    /// Return a fast loop implementation that tests 8 bytes at a time for the few bytes
+    /// leaving the current state, in the spirit of `memchr`. Like [`Self::impl_fast_loop`],
+    /// offset will be the first offset that transitions away from the current state.
+    fn impl_swar_loop(&mut self, exits: SwarExits) -> TokenStream {
+        self.swar_loop = true;
+
+        let SwarExits { bytes, non_ascii } = exits;
+
+        let byte_tests = bytes.iter().map(|&byte| {
+            let byte = byte_to_tokens(byte);
+            quote!(byte == #byte)
+        });
+        let non_ascii_test = non_ascii.then(|| quote!(byte >= 0x80));
+        let loop_test = byte_tests.chain(non_ascii_test);
+
+        // Sets high bits in the bytes of `word` equal to `byte`, as in `Lexer::find_byte`
+        let word_tests = bytes.iter().map(|&byte| {
+            let byte = byte_to_tokens(byte);
+            quote! {{
+                let xor = word ^ (LO * #byte as ::core::primitive::u64);
+                xor.wrapping_sub(LO) & !xor
+            }}
+        });
+        let non_ascii_word = non_ascii.then(|| quote!(word));
+        let word_test = word_tests.chain(non_ascii_word);
+
+        quote! {
+            #[inline]
+            fn loop_test(byte: ::core::primitive::u8) -> ::core::primitive::bool {
+                #(#loop_test)||*
+            }
+            #[inline]
+            fn word_test(word: ::core::primitive::u64) -> ::core::primitive::bool {
+                const LO: ::core::primitive::u64 = ::core::primitive::u64::from_ne_bytes([0x01; 8]);
+                const HI: ::core::primitive::u64 = ::core::primitive::u64::from_ne_bytes([0x80; 8]);
+                (#(#word_test)|*) & HI != 0
+            }
+            _swar_loop!(lex, loop_test, word_test, offset);
+        }
+    }
+}
+
+/// The bytes leaving a self-looping state, when there are few enough of them to be searched
+/// for 8 bytes at a time.
+struct SwarExits {
+    /// ASCII bytes leaving the state
+    bytes: Vec<u8>,
+    /// Whether all non-ASCII bytes leave the state, as is usual in UTF-8 mode
+    non_ascii: bool,
+}
+
+impl SwarExits {
+    /// Most bytes that can be searched for on top of non-ASCII ones.
+    const MAX_BYTES: usize = 3;
+
+    fn new(self_edge: &ByteClass) -> Option<Self> {
+        let table = self_edge.to_table();
+        let (ascii, non_ascii) = table.split_at(0x80);
+
+        let non_ascii = match (non_ascii.iter().all(|&b| b), non_ascii.iter().any(|&b| b)) {
+            (true, _) => false,
+            (false, false) => true,
+            (false, true) => return None,
+        };
+
+        let bytes = (0..0x80u8)
+            .filter(|&byte| !ascii[byte as usize])
+            .collect::<Vec<_>>();
+
+        if (bytes.is_empty() && !non_ascii) || bytes.len() > Self::MAX_BYTES {
+            return None;
+        }
+
+        Some(SwarExits { bytes, non_ascii })
+    }
+}
+
+/// This macro is included with the generated code when one of the fast loops uses
+/// [`Generator::impl_swar_loop`]. Like `_fast_loop!`, but tests 8 bytes at a time using
+/// a test on `u64` words, then finds the exact offset one byte at a time.
+pub fn swar_loop_macro(read: &Ident) -> TokenStream {
+    quote! {
+        macro_rules! _swar_loop {
+            ($lex:ident, $test:ident, $word_test:ident, $offset:ident) => {
+                while let _Option::Some(arr) = $lex.#read::<&[::core::primitive::u8; 8]>($offset) {
+                    if $word_test(::core::primitive::u64::from_ne_bytes(*arr)) {
+                        break;
+                    }
+                    $offset += 8;
+                }
+
+                while let _Option::Some(byte) = $lex.#read::<::core::primitive::u8>($offset) {
+                    if $test(byte) {
+                        break;
+                    }
+                    $offset += 1;
+                }
+            };
+        }
+    }
 }
 
 /// This macro is included with the generated code. It is used to manually unroll the fast_loop
 /// loop.
-pub fn fast_loop_macro(unroll_factor: usize) -> TokenStream {
+pub fn fast_loop_macro(unroll_factor: usize, read: &Ident) -> TokenStream {
     let index = (0..unroll_factor).collect::<Vec<_>>();
 
     quote! {
@@ -58,12 +165,12 @@ pub fn fast_loop_macro(unroll_factor: usize) -> TokenStream {
             ($lex:ident, $test:ident, $offset:ident) => {
                 // Do one bounds check for multiple bytes till EOF
                 'fast_loop: {
-                    while let _Option::Some(arr) = $lex.read::<&[::core::primitive::u8; #unroll_factor]>($offset) {
+                    while let _Option::Some(arr) = $lex.#read::<&[::core::primitive::u8; #unroll_factor]>($offset) {
                         #(if $test(arr[#index])   { $offset += #index; break 'fast_loop; })*
                         $offset += #unroll_factor;
                     }
 
-                    while let _Option::Some(byte) = $lex.read::<::core::primitive::u8>($offset) {
+                    while let _Option::Some(byte) = $lex.#read::<::core::primitive::u8>($offset) {
                         if $test(byte) { break 'fast_loop; }
                         $offset += 1;
                     }
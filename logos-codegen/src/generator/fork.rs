@@ -1,3 +1,4 @@
+use std::cmp::Reverse;
 use std::collections::HashSet;
 
 use proc_macro2::TokenStream;
@@ -5,11 +6,80 @@ use quote::{quote, ToTokens, TokenStreamExt};
 
 use crate::{
     generator::byte_to_tokens,
-    graph::{Comparisons, State, StateData},
+    graph::{ByteClass, Comparisons, State, StateData},
 };
 
 use super::Generator;
 
+/// Rough cost of testing a byte with a LUT lookup, counted in comparisons.
+const LUT_COST: u64 = 2;
+
+/// Rough cost of a jump table lookup, counted in comparisons.
+const JUMP_TABLE_COST: u64 = 4;
+
+/// How a fork finds the transition for the next byte.
+enum ForkKind<'s> {
+    /// A test for each edge, made in this order
+    Tests(Vec<&'s (ByteClass, State)>),
+    /// A jump table, after testing the given edge first
+    Table(Option<&'s (ByteClass, State)>),
+}
+
+/// How a fork tests whether the next byte is in a byte class.
+enum ByteTest {
+    /// A lookup in a LUT
+    Lut,
+    /// Comparisons, made in this order
+    Compare(Vec<Comparisons>),
+}
+
+impl ByteTest {
+    /// Without byte counts, classes needing more than 2 comparisons are tested with a LUT.
+    /// With counts, the comparisons matching the most bytes are made first, and a LUT is only
+    /// used if they still cost more on average.
+    fn new(byte_class: &ByteClass, counts: Option<&[u64; 256]>) -> Self {
+        let mut comparisons = byte_class.impl_with_cmp();
+        let Some(counts) = counts else {
+            let cmp_count: usize = comparisons.iter().map(|cmp| cmp.count_ops()).sum();
+            return match cmp_count > 2 {
+                true => ByteTest::Lut,
+                false => ByteTest::Compare(comparisons),
+            };
+        };
+
+        comparisons.sort_by_key(|cmp| Reverse(cmp.count(counts)));
+        let compare = ByteTest::Compare(comparisons);
+        match compare.cost(counts) > LUT_COST * counts.iter().sum::<u64>() {
+            true => ByteTest::Lut,
+            false => compare,
+        }
+    }
+
+    /// Cost of testing `byte`.
+    fn cost_of(&self, byte: u8) -> u64 {
+        match self {
+            ByteTest::Lut => LUT_COST,
+            ByteTest::Compare(comparisons) => {
+                let mut cost = 0;
+                for cmp in comparisons {
+                    cost += cmp.count_ops() as u64;
+                    if cmp.contains(byte) {
+                        break;
+                    }
+                }
+                cost
+            }
+        }
+    }
+
+    /// Cost of testing all the bytes in `counts`.
+    fn cost(&self, counts: &[u64; 256]) -> u64 {
+        (0..=u8::MAX)
+            .map(|byte| counts[byte as usize] * self.cost_of(byte))
+            .sum()
+    }
+}
+
 impl<'a> Generator<'a> {
     pub fn impl_fork(
         &mut self,
@@ -17,13 +87,52 @@ impl<'a> Generator<'a> {
         state_data: &StateData,
         ignore_self: bool,
     ) -> TokenStream {
-        if state_data.normal.len() > 2 {
-            self.impl_fork_table(state, state_data, ignore_self)
-        } else {
-            self.impl_fork_match(state, state_data, ignore_self)
+        let edges = state_data
+            .normal
+            .iter()
+            .filter(|(_, next_state)| !(ignore_self && next_state == &state))
+            .collect::<Vec<_>>();
+        let counts = self.fork_counts(state, state_data, ignore_self);
+
+        let kind = match &counts {
+            None if state_data.normal.len() > 2 => ForkKind::Table(None),
+            None => ForkKind::Tests(edges),
+            Some(counts) => profiled_fork_kind(edges, counts),
+        };
+
+        match kind {
+            ForkKind::Tests(edges) => {
+                self.impl_fork_match(state, state_data, edges, counts.as_ref())
+            }
+            ForkKind::Table(first) => {
+                self.impl_fork_table(state, state_data, ignore_self, first, counts.as_ref())
+            }
         }
     }
 
+    /// Number of times each byte was read in `state` in the profile, if there is a profile
+    /// and any byte tested by the fork was read.
+    fn fork_counts(
+        &self,
+        state: State,
+        state_data: &StateData,
+        ignore_self: bool,
+    ) -> Option<[u64; 256]> {
+        let profile = self.config.profile.as_ref()?;
+        let mut counts = *profile.byte_counts(state);
+
+        // Bytes looping back to the state are consumed before the fork, by a fast loop
+        if ignore_self {
+            for (byte_class, _) in state_data.normal.iter().filter(|(_, next)| *next == state) {
+                for byte in byte_class.ranges.iter().flat_map(|range| range.clone()) {
+                    counts[byte as usize] = 0;
+                }
+            }
+        }
+
+        counts.iter().any(|&count| count > 0).then_some(counts)
+    }
+
     // Generate code for encountering the end of input.
     // If we are not in the middle of a token, return None (the iterator is ended)
     // If the state has an EOI node, transition to it.
@@ -61,22 +170,48 @@ impl<'a> Generator<'a> {
         &mut self,
         state: State,
         state_data: &StateData,
-        ignore_self: bool,
+        edges: Vec<&(ByteClass, State)>,
+        counts: Option<&[u64; 256]>,
     ) -> TokenStream {
         // Generate a match arm for each byte class, with each body being a state transition
         let mut inner_cases = TokenStream::new();
-        for (byte_class, next_state) in &state_data.normal {
-            if ignore_self && next_state == &state {
-                continue;
-            }
+        for (byte_class, next_state) in edges {
+            let condition = self.byte_test_condition(byte_class, counts);
+            let transition = self.state_transition(*next_state);
+            inner_cases.append_all(quote! {
+                if #condition {
+                    offset += 1;
+                    #transition
+                }
+            });
+        }
 
-            let comparisons = byte_class.impl_with_cmp();
-            let cmp_count: usize = comparisons.iter().map(|cmp| cmp.count_ops()).sum();
+        let eoi = self.fork_eoi(state, state_data);
+        let read_byte = self.read_byte();
+        quote! {
+            let other = #read_byte;
+            if let _Option::Some(byte) = other {
+                #inner_cases
+            } else {
+                #eoi
+            }
+            _take_action!(lex, offset, context, state)
+        }
+    }
 
-            let condition = if cmp_count > 2 {
+    /// Generates the condition testing whether `byte` is in `byte_class`, with a few
+    /// comparisons or a LUT lookup, see [ByteTest::new].
+    fn byte_test_condition(
+        &mut self,
+        byte_class: &ByteClass,
+        counts: Option<&[u64; 256]>,
+    ) -> TokenStream {
+        match ByteTest::new(byte_class, counts) {
+            ByteTest::Lut => {
                 let (test_ident, test_mask) = self.add_test_to_lut(byte_class);
                 quote! { #test_ident[byte as ::core::primitive::usize] & #test_mask != 0 }
-            } else {
+            }
+            ByteTest::Compare(comparisons) => {
                 let sub_conditions = comparisons
                     .into_iter()
                     .map(|cmp| {
@@ -98,25 +233,7 @@ impl<'a> Generator<'a> {
                     .collect::<Vec<_>>();
 
                 quote! { #(#sub_conditions) ||* }
-            };
-            let transition = self.state_transition(*next_state);
-            inner_cases.append_all(quote! {
-                if #condition {
-                    offset += 1;
-                    #transition
-                }
-            });
-        }
-
-        let eoi = self.fork_eoi(state, state_data);
-        quote! {
-            let other = lex.read::<::core::primitive::u8>(offset);
-            if let _Option::Some(byte) = other {
-                #inner_cases
-            } else {
-                #eoi
             }
-            _take_action!(lex, offset, context, state)
         }
     }
 
@@ -125,6 +242,8 @@ impl<'a> Generator<'a> {
         state: State,
         state_data: &StateData,
         ignore_self: bool,
+        first: Option<&(ByteClass, State)>,
+        counts: Option<&[u64; 256]>,
     ) -> TokenStream {
         // Generate a match arm for each byte class, with each body being a state transition
         let mut table = vec![None; 256];
@@ -214,11 +333,25 @@ impl<'a> Generator<'a> {
             }
         };
 
+        // Skip the table lookup for the byte class most of the input goes through
+        let first = first.map(|(byte_class, next_state)| {
+            let condition = self.byte_test_condition(byte_class, counts);
+            let transition = self.state_transition(*next_state);
+            quote! {
+                if #condition {
+                    offset += 1;
+                    #transition
+                }
+            }
+        });
+
         let eoi = self.fork_eoi(state, state_data);
+        let read_byte = self.read_byte();
 
         quote! {
-            let other = lex.read::<::core::primitive::u8>(offset);
+            let other = #read_byte;
             if let _Option::Some(byte) = other {
+                #first
                 #body
             } else {
                 #eoi
@@ -227,3 +360,48 @@ impl<'a> Generator<'a> {
         }
     }
 }
+
+/// Picks the cheapest way for a fork to test the next byte against `edges`, according to how
+/// often each byte was read in the profile: testing each edge in turn, most frequent first, or
+/// a jump table, optionally after testing the most frequent edge.
+fn profiled_fork_kind<'s>(
+    mut edges: Vec<&'s (ByteClass, State)>,
+    counts: &[u64; 256],
+) -> ForkKind<'s> {
+    edges.sort_by_key(|(byte_class, _)| Reverse(byte_class.count(counts)));
+    let tests = edges
+        .iter()
+        .map(|(byte_class, _)| ByteTest::new(byte_class, Some(counts)))
+        .collect::<Vec<_>>();
+
+    let tests_cost = (0..=u8::MAX)
+        .map(|byte| {
+            let mut cost = 0;
+            for ((byte_class, _), test) in edges.iter().zip(&tests) {
+                cost += test.cost_of(byte);
+                if byte_class.contains(byte) {
+                    break;
+                }
+            }
+            counts[byte as usize] * cost
+        })
+        .sum::<u64>();
+    let table_cost = JUMP_TABLE_COST * counts.iter().sum::<u64>();
+    let first_then_table_cost = edges
+        .first()
+        .zip(tests.first())
+        .map(|((byte_class, _), test)| {
+            let missed = counts.iter().sum::<u64>() - byte_class.count(counts);
+            test.cost(counts) + JUMP_TABLE_COST * missed
+        });
+
+    let (table_cost, first) = match first_then_table_cost {
+        Some(cost) if cost < table_cost => (cost, edges.first().copied()),
+        _ => (table_cost, None),
+    };
+
+    match tests_cost <= table_cost {
+        true => ForkKind::Tests(edges),
+        false => ForkKind::Table(first),
+    }
+}
@@ -54,15 +54,74 @@ impl Generator<'_> {
         }
     }
 
+    /// Generates the `_too_long` macro, which returns the error for a token growing past its
+    /// length limit, ending at the current offset. It is only needed if any leaf has a
+    /// `max_len`.
+    pub fn too_long_macro(&self) -> Option<TokenStream> {
+        if self
+            .graph
+            .leaves()
+            .iter()
+            .all(|leaf| leaf.max_len.is_none())
+        {
+            return None;
+        }
+
+        let on_error = self
+            .on_token
+            .as_ref()
+            .map(|_| quote!(_on_token($lex, _TokenEvent::Error(&err));));
+
+        Some(quote! {
+            macro_rules! _too_long {
+                ($lex:ident, $offset:expr, $limit:expr) => {{
+                    $lex.set_rule(_Option::None);
+                    $lex.end_to_boundary($offset);
+                    let err = ::core::convert::From::from(_TokenTooLong { limit: $limit });
+                    #on_error
+                    return _Option::Some(_Result::Err(err));
+                }}
+            }
+        })
+    }
+
     /// This function generates the _take_action macro. This macro is called when there are no more
     /// transitions to follow. It calls the _get_action function, which tells the state machine
     /// what to do next, and applies that action to the state machine's internal state.
     pub fn take_action_macro(&self) -> TokenStream {
         // This is the code block used to transition the lexer to a new state
-        let state_ident = self.state_value(self.graph.root());
-        let restart_lex = match self.config.use_state_machine_codegen {
-            true => quote! { $state = #state_ident; continue; },
-            false => quote! { return #state_ident($lex, $offset, $context); },
+        let restart_lex = if self.config.use_table_codegen {
+            quote! { $state = ROOT; continue; }
+        } else {
+            let state_ident = self.state_value(self.graph.root());
+            match self.config.use_state_machine_codegen {
+                true => quote! { $state = #state_ident; continue; },
+                false => quote! { return #state_ident($lex, $offset, $context); },
+            }
+        };
+
+        // Calls to the hook set with `#[logos(on_token = ...)]`
+        let [on_emit, on_skip, on_error] = match self.on_token {
+            Some(_) => [
+                quote!(_on_token($lex, _TokenEvent::Token(&tok));),
+                quote! {
+                    if let _Option::Some(leaf) = $context {
+                        _on_token($lex, _TokenEvent::Skip(leaf as ::core::primitive::usize));
+                    }
+                },
+                quote!(_on_token($lex, _TokenEvent::Error(&err));),
+            ],
+            None => Default::default(),
+        };
+        let default_error = match self.on_token {
+            Some(_) => quote! {
+                let err = _make_error($lex);
+                #on_error
+                return _Option::Some(_Result::Err(err));
+            },
+            None => quote! {
+                return _Option::Some(_Result::Err(_make_error($lex)));
+            },
         };
 
         quote! {
@@ -71,19 +130,22 @@ impl Generator<'_> {
                     let action = _get_action($lex, $offset, $context);
                     match action {
                         CallbackResult::Emit(tok) => {
+                            #on_emit
                             return _Option::Some(_Result::Ok(tok));
                         },
                         CallbackResult::Skip => {
+                            #on_skip
                             $lex.trivia();
                             $offset = $lex.offset();
                             $context = _Option::None;
                             #restart_lex
                         },
                         CallbackResult::Error(err) => {
+                            #on_error
                             return _Option::Some(_Result::Err(err));
                         },
                         CallbackResult::DefaultError => {
-                            return _Option::Some(_Result::Err(_make_error($lex)));
+                            #default_error
                         },
                     }
                 }}
@@ -1,21 +1,30 @@
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
 
-use fast_loop::fast_loop_macro;
+use fast_loop::{fast_loop_macro, swar_loop_macro};
 use fnv::FnvHashMap as Map;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::Ident;
 
-use crate::graph::{ByteClass, Graph, State, StateType};
+use crate::graph::{ByteClass, Graph, Profile, State, StateType};
 use crate::leaf::{Callback, InlineCallback};
 use crate::util::ToIdent;
 
+mod chain;
+mod const_fn;
 mod fast_loop;
 mod fork;
 mod leaf;
+mod table;
 
 pub struct Config {
     pub use_state_machine_codegen: bool,
+    pub use_table_codegen: bool,
+    pub forbid_unsafe: bool,
+    pub padded: bool,
+    /// Transition counts from `#[logos(profile_corpus = "...")]`, used to order byte tests
+    pub profile: Option<Profile>,
 }
 
 pub struct Generator<'a> {
@@ -38,11 +47,20 @@ pub struct Generator<'a> {
     leaf_idents: Vec<[Ident; 2]>,
     /// Callback for the default error type
     error_callback: &'a Option<Callback>,
+    /// Hook called after every token, error and skip
+    on_token: &'a Option<TokenStream>,
     /// Bit masks that will be compressed into LUTs for fast looping
     loop_masks: HashMap<[bool; 256], usize>,
+    /// Whether any fast loop searches 8 bytes at a time, needing the `_swar_loop` macro
+    swar_loop: bool,
+    /// Longest token that can still be matched from each state, if limited with `max_len`
+    max_token_lens: Vec<Option<usize>>,
+    /// States from which a linear chain of bytes is compared at once
+    chain_heads: Vec<bool>,
 }
 
 impl<'a> Generator<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: Config,
         name: &'a Ident,
@@ -51,6 +69,7 @@ impl<'a> Generator<'a> {
         lifetime_bounds: &'a TokenStream,
         graph: &'a Graph,
         error_callback: &'a Option<Callback>,
+        on_token: &'a Option<TokenStream>,
     ) -> Self {
         let state_idents = graph
             .iter_states()
@@ -74,6 +93,11 @@ impl<'a> Generator<'a> {
             })
             .collect();
 
+        let max_token_lens = match graph.leaves().iter().any(|leaf| leaf.max_len.is_some()) {
+            true => graph.max_token_lens(),
+            false => Vec::new(),
+        };
+
         Generator {
             config,
             name,
@@ -84,19 +108,27 @@ impl<'a> Generator<'a> {
             state_idents,
             leaf_idents,
             error_callback,
+            on_token,
             loop_masks: HashMap::new(),
+            swar_loop: false,
+            max_token_lens,
+            chain_heads: graph.chain_heads(),
         }
     }
 
     /// Generates the implementation (body) of the [Logos::lex] function
     pub fn generate(&mut self) -> TokenStream {
-        let mut states = self.graph.iter_states().collect::<Vec<_>>();
-        // Sort for repeatability (not dependent on hashmap iteration order)
-        states.sort_unstable();
-        let states_rendered = states
-            .iter()
-            .map(|&state| self.generate_state(state))
-            .collect::<Vec<_>>();
+        let states_rendered = if self.config.use_table_codegen {
+            Vec::new()
+        } else {
+            let mut states = self.graph.iter_states().collect::<Vec<_>>();
+            // Sort for repeatability (not dependent on hashmap iteration order)
+            states.sort_unstable();
+            states
+                .iter()
+                .map(|&state| self.generate_state(state))
+                .collect::<Vec<_>>()
+        };
 
         let init_state = self.get_ident(self.graph.root());
         let mut all_idents_pascal = self
@@ -107,30 +139,40 @@ impl<'a> Generator<'a> {
         // Sort for repeatability (not dependent on hashmap iteration order)
         all_idents_pascal.sort_unstable();
 
-        let leaves_pascal = self
-            .leaf_idents
-            .iter()
-            .map(|[_snake, pascal]| pascal)
-            .collect::<Vec<_>>();
-        let leaves_index = 0..(self.graph.leaves().len() as isize);
-
+        let leaf_enum = self.leaf_enum();
         let make_token_fn = self.make_token_fn();
-        let fast_loop_macro = fast_loop_macro(8);
+        let on_token_fn = self.on_token_fn();
+        // The table codegen has no per-state code to put fast loops in
+        let fast_loop_macro = match self.config.use_table_codegen {
+            true => None,
+            false => Some(fast_loop_macro(8, &self.read_method())),
+        };
+        let swar_loop_macro = match self.swar_loop {
+            true => Some(swar_loop_macro(&self.read_method())),
+            false => None,
+        };
         let take_action_macro = self.take_action_macro();
+        let too_long_macro = self.too_long_macro();
         let loop_luts = self.render_luts();
 
         let common = quote! {
                 #fast_loop_macro
+                #swar_loop_macro
                 #take_action_macro
+                #too_long_macro
                 #loop_luts
                 #make_token_fn
-                #[derive(::core::clone::Clone, ::core::marker::Copy)]
-                enum LogosLeaf {
-                    #(#leaves_pascal = #leaves_index),*
-                }
+                #on_token_fn
+                #leaf_enum
         };
 
-        if self.config.use_state_machine_codegen {
+        if self.config.use_table_codegen {
+            let table = self.generate_table();
+            quote! {
+                #common
+                #table
+            }
+        } else if self.config.use_state_machine_codegen {
             quote! {
                 #common
                 #[derive(::core::clone::Clone, ::core::marker::Copy)]
@@ -155,6 +197,70 @@ impl<'a> Generator<'a> {
         }
     }
 
+    /// Generates the `LogosLeaf` enum, with a variant for each leaf of the graph.
+    fn leaf_enum(&self) -> TokenStream {
+        let leaves_pascal = self
+            .leaf_idents
+            .iter()
+            .map(|[_snake, pascal]| pascal)
+            .collect::<Vec<_>>();
+        let leaves_index = 0..(self.graph.leaves().len() as isize);
+
+        quote! {
+            #[derive(::core::clone::Clone, ::core::marker::Copy)]
+            enum LogosLeaf {
+                #(#leaves_pascal = #leaves_index),*
+            }
+        }
+    }
+
+    /// Returns the longest token that can still be matched from `state`, if limited.
+    fn max_token_len(&self, state: State) -> Option<usize> {
+        self.max_token_lens.get(state.index()).copied().flatten()
+    }
+
+    /// Generates the check of a limited state, raising the too long error if the state has
+    /// already read `max_len` bytes and the next byte has a transition.
+    fn impl_max_len(&self, state: State, max_len: usize) -> TokenStream {
+        let state_data = self.graph.get_state(state);
+        if state_data.normal.is_empty() {
+            return TokenStream::new();
+        }
+
+        let mut ranges = state_data
+            .normal
+            .iter()
+            .flat_map(|(byte_class, _)| byte_class.ranges.iter().cloned())
+            .collect::<Vec<_>>();
+        ranges.sort_unstable_by_key(|range| *range.start());
+        let mut merged: Vec<RangeInclusive<u8>> = Vec::new();
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if *range.start() as usize <= *last.end() as usize + 1 => {
+                    *last = *last.start()..=*last.end().max(range.end());
+                }
+                _ => merged.push(range),
+            }
+        }
+        let ranges = merged
+            .iter()
+            .map(|range| match (range.start(), range.end()) {
+                (start, end) if start == end => quote!(#start),
+                (start, end) => quote!(#start..=#end),
+            });
+        let read_byte = self.read_byte();
+
+        quote! {
+            if offset - lex.offset() > #max_len {
+                if let _Option::Some(byte) = #read_byte {
+                    if ::core::matches!(byte, #(#ranges)|*) {
+                        _too_long!(lex, offset, #max_len);
+                    }
+                }
+            }
+        }
+    }
+
     fn get_ident(&self, state: State) -> &Ident {
         let idx = match self.config.use_state_machine_codegen {
             true => 1,
@@ -181,7 +287,28 @@ impl<'a> Generator<'a> {
             .graph
             .leaves()
             .iter()
-            .map(|leaf| self.generate_callback(leaf))
+            .enumerate()
+            .map(|(index, leaf)| {
+                let index = index as u32;
+                let set_rule = quote!(lex.set_rule(_Option::Some(#index)););
+                let callback = self.generate_callback(leaf);
+                // States only stop tokens that can't fit any leaf, check this one's own limit
+                let max_len = leaf.max_len.map(|max_len| {
+                    quote! {
+                        if lex.span().len() > #max_len {
+                            return CallbackResult::Error(::core::convert::From::from(
+                                _TokenTooLong { limit: #max_len },
+                            ));
+                        }
+                    }
+                });
+
+                quote! {
+                    #set_rule
+                    #max_len
+                    #callback
+                }
+            })
             .collect::<Vec<_>>();
         let leaf_indices = self
             .leaf_idents
@@ -225,6 +352,7 @@ impl<'a> Generator<'a> {
             {
                 match context {
                     _Option::None => {
+                        lex.set_rule(_Option::None);
                         lex.end_to_boundary(offset.max(lex.offset() + 1));
                         CallbackResult::Error(_make_error(lex))
                     },
@@ -237,6 +365,44 @@ impl<'a> Generator<'a> {
         }
     }
 
+    /// Generates the `_on_token` function wrapping the hook set with
+    /// `#[logos(on_token = ...)]`, if any.
+    fn on_token_fn(&self) -> Option<TokenStream> {
+        let hook = self.on_token.as_ref()?;
+        let this = self.this;
+        let src_lt = self.source_lifetime;
+        let lt_bounds = self.lifetime_bounds;
+
+        Some(quote! {
+            #[inline]
+            fn _on_token #lt_bounds (lex: &mut _Lexer<#src_lt, #this>, event: _TokenEvent<'_, #src_lt, #this>) {
+                #hook(lex, event)
+            }
+        })
+    }
+
+    /// Returns the `LexerInternal` method used to read bytes, which is `read_safe`
+    /// under `#[logos(forbid_unsafe)]`.
+    fn read_method(&self) -> Ident {
+        match self.config.forbid_unsafe {
+            true => "read_safe".to_ident(),
+            false => "read".to_ident(),
+        }
+    }
+
+    /// Returns the expression reading the byte at `offset` on every transition. Padded sources
+    /// read it with the `_read_byte!` macro of the runtime crate, which skips bounds checks
+    /// unless its `forbid_unsafe` feature is enabled.
+    fn read_byte(&self) -> TokenStream {
+        if self.config.forbid_unsafe {
+            quote!(lex.read_safe::<::core::primitive::u8>(offset))
+        } else if self.config.padded {
+            quote!(_read_byte!(lex, offset))
+        } else {
+            quote!(lex.read::<::core::primitive::u8>(offset))
+        }
+    }
+
     /// Generates the code to transition to a state.
     fn state_transition(&self, state: State) -> TokenStream {
         self.state_action(self.state_value(state))
@@ -296,16 +462,26 @@ impl<'a> Generator<'a> {
             StateType { .. } => quote!(),
         };
 
-        let fast_loop = self.maybe_impl_fast_loop(state);
-        let fork = self.impl_fork(state, state_data, true);
+        // Once a state has read `max_len` bytes, reading one more byte can only match tokens
+        // over the limit, so the token is too long as soon as the next byte has a transition.
+        // Limited states read one byte at a time instead of using fast loops, so they stop
+        // at the first byte past the limit.
+        let (max_len, fast_loop) = match self.max_token_len(state) {
+            Some(max_len) => (self.impl_max_len(state, max_len), TokenStream::new()),
+            None => (TokenStream::new(), self.maybe_impl_fast_loop(state)),
+        };
+        let chain = self.maybe_impl_chain(state);
+        let fork = self.impl_fork(state, state_data, self.max_token_len(state).is_none());
 
         // Wrap body in a match arm or function depending on the current codegen
         let this_ident = self.get_ident(state);
         if self.config.use_state_machine_codegen {
             quote! {
                 LogosState::#this_ident => {
+                    #max_len
                     #fast_loop
                     #setup
+                    #chain
                     #fork
                 }
             }
@@ -316,8 +492,10 @@ impl<'a> Generator<'a> {
             quote! {
                 fn #this_ident #lt_bounds (lex: &mut _Lexer<#src_lt, #this>, mut offset: ::core::primitive::usize, mut context: _Option<LogosLeaf>)
                     -> _Option<_Result<#this, <#this as Logos<#src_lt>>::Error>> {
+                    #max_len
                     #fast_loop
                     #setup
+                    #chain
                     #fork
                 }
             }
@@ -349,6 +527,11 @@ impl<'a> Generator<'a> {
         (ident, loop_mask)
     }
 
+    /// Returns the number of LUTs rendered by [`Self::render_luts`].
+    pub fn lut_count(&self) -> usize {
+        self.loop_masks.len().div_ceil(8)
+    }
+
     /// Stack the bit tables into chunks of 8 and render them as byte table constants into a
     /// TokenStream.
     pub fn render_luts(&self) -> TokenStream {
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+
+use crate::graph::{State, StateType};
+
+use super::Generator;
+
+impl Generator<'_> {
+    /// Generates the body of the table codegen. Instead of emitting code for every state,
+    /// the graph is compressed into a map from bytes to byte classes, and a table of
+    /// transitions indexed by state and byte class. A single loop then walks the tables.
+    ///
+    /// The loop mirrors the states of the other codegens: set the context if the state
+    /// matches a leaf, then follow the transition for the next byte, or for the end of
+    /// input, and take the action once there is no transition left.
+    pub fn generate_table(&self) -> TokenStream {
+        let definitions = self.table_definitions();
+        let read_byte = self.read_byte();
+        let max_len = (!self.max_token_lens.is_empty()).then(|| {
+            quote! {
+                let max_len = MAX_LENS[state as ::core::primitive::usize];
+                if offset - lex.offset() > max_len {
+                    if let _Option::Some(byte) = #read_byte {
+                        let class = CLASSES[byte as ::core::primitive::usize] as ::core::primitive::usize;
+                        if TRANSITIONS[state as ::core::primitive::usize * CLASS_COUNT + class] != DEAD {
+                            _too_long!(lex, offset, max_len);
+                        }
+                    }
+                }
+            }
+        });
+
+        quote! {
+            #definitions
+
+            let mut state = ROOT;
+            let mut offset = lex.offset();
+            let mut context: _Option<LogosLeaf> = _Option::None;
+            loop {
+                #max_len
+                let info = STATES[state as ::core::primitive::usize];
+                if let _Option::Some(leaf) = info.context {
+                    // The 1 comes from the 1 byte delayed match behavior
+                    // of the regex-automata crate.
+                    lex.end(if info.early { offset } else { offset - 1 });
+                    context = _Option::Some(leaf);
+                }
+
+                let next = match #read_byte {
+                    _Option::Some(byte) => {
+                        let class = CLASSES[byte as ::core::primitive::usize] as ::core::primitive::usize;
+                        TRANSITIONS[state as ::core::primitive::usize * CLASS_COUNT + class]
+                    }
+                    _Option::None => {
+                        if info.can_continue && lex.is_prefix() {
+                            lex.end(lex.offset());
+                            return _Option::None;
+                        }
+                        if state == ROOT && lex.offset() == offset {
+                            return _Option::None;
+                        }
+                        info.eoi
+                    }
+                };
+
+                if next != DEAD {
+                    offset += 1;
+                    state = next;
+                    continue;
+                }
+
+                _take_action!(lex, offset, context, state)
+            }
+        }
+    }
+
+    /// Generates the tables walked by [`Self::generate_table`]: the `LogosStateInfo` of
+    /// every state, the byte classes and the transitions. `LogosLeaf` must be in scope.
+    ///
+    /// If any leaf has a `max_len`, `MAX_LENS` holds the longest token each state can still
+    /// match. Once a state has read that many bytes, any transition leads past the limit.
+    pub fn table_definitions(&self) -> TokenStream {
+        let state_count = self.graph.iter_states().count();
+
+        // The number of states doubles as the marker for a missing transition
+        let state_ty = match state_count {
+            n if n < u8::MAX as usize => quote!(::core::primitive::u8),
+            n if n < u16::MAX as usize => quote!(::core::primitive::u16),
+            _ => quote!(::core::primitive::u32),
+        };
+        let dead = Literal::usize_unsuffixed(state_count);
+        let root = Literal::usize_unsuffixed(self.graph.root().index());
+
+        // Bytes that lead to the same state from every state share a class
+        let mut columns = vec![vec![state_count; state_count]; 256];
+        for state in self.graph.iter_states() {
+            for (byte_class, next_state) in &self.graph.get_state(state).normal {
+                for range in &byte_class.ranges {
+                    for byte in range.clone() {
+                        columns[byte as usize][state.index()] = next_state.index();
+                    }
+                }
+            }
+        }
+
+        let mut class_ids = HashMap::new();
+        let mut classes = Vec::new();
+        let byte_classes = columns
+            .iter()
+            .map(|column| {
+                let id = *class_ids.entry(column).or_insert_with(|| {
+                    classes.push(column);
+                    classes.len() - 1
+                });
+                Literal::u8_unsuffixed(id as u8)
+            })
+            .collect::<Vec<_>>();
+
+        let class_count = Literal::usize_unsuffixed(classes.len());
+        let transitions = (0..state_count)
+            .flat_map(|state| classes.iter().map(move |column| column[state]))
+            .map(Literal::usize_unsuffixed)
+            .collect::<Vec<_>>();
+
+        let states_len = Literal::usize_unsuffixed(state_count);
+        let states = self
+            .graph
+            .iter_states()
+            .map(|state| self.table_state_info(state, state_count))
+            .collect::<Vec<_>>();
+
+        let max_lens = (!self.max_token_lens.is_empty()).then(|| {
+            let max_lens = self
+                .graph
+                .iter_states()
+                .map(|state| match self.max_token_len(state) {
+                    Some(max_len) => quote!(#max_len),
+                    None => quote!(::core::primitive::usize::MAX),
+                });
+            quote! {
+                const MAX_LENS: [::core::primitive::usize; #states_len] = [#(#max_lens),*];
+            }
+        });
+
+        quote! {
+            #[derive(::core::clone::Clone, ::core::marker::Copy)]
+            struct LogosStateInfo {
+                context: _Option<LogosLeaf>,
+                early: ::core::primitive::bool,
+                eoi: #state_ty,
+                can_continue: ::core::primitive::bool,
+            }
+            const ROOT: #state_ty = #root;
+            const DEAD: #state_ty = #dead;
+            const CLASS_COUNT: ::core::primitive::usize = #class_count;
+            const CLASSES: [::core::primitive::u8; 256] = [#(#byte_classes),*];
+            const TRANSITIONS: [#state_ty; #states_len * #class_count] = [#(#transitions),*];
+            const STATES: [LogosStateInfo; #states_len] = [#(#states),*];
+            #max_lens
+        }
+    }
+
+    /// Generates the entry of the `STATES` table for a state.
+    fn table_state_info(&self, state: State, state_count: usize) -> TokenStream {
+        let state_data = self.graph.get_state(state);
+
+        let (context, early) = match state_data.state_type {
+            StateType {
+                early: Some(idx), ..
+            } => (Some(idx), true),
+            StateType {
+                accept: Some(idx), ..
+            } => (Some(idx), false),
+            StateType { .. } => (None, false),
+        };
+        let context = match context {
+            Some(idx) => {
+                let leaf = &self.leaf_idents[idx.0][1];
+                quote!(_Option::Some(LogosLeaf::#leaf))
+            }
+            None => quote!(_Option::None),
+        };
+        let eoi = Literal::usize_unsuffixed(state_data.eoi.map_or(state_count, |s| s.index()));
+        let can_continue = !state_data.normal.is_empty();
+
+        quote! {
+            LogosStateInfo {
+                context: #context,
+                early: #early,
+                eoi: #eoi,
+                can_continue: #can_continue,
+            }
+        }
+    }
+}
@@ -25,9 +25,12 @@ pub fn iter_matches<'a>(state_id: StateID, dfa: &'a OwnedDFA) -> impl Iterator<I
 }
 
 /// Returns an iterator over the child states of a given dfa state. Returns children in order of
-/// input byte `(0..=255)`, then eoi. No deduplication of child states is performed.
+/// byte class, then eoi. Only one byte of each class is looked up, since all bytes of a class
+/// lead to the same state. No deduplication of child states is performed.
 pub fn iter_children<'a>(dfa: &'a OwnedDFA, state: StateID) -> impl Iterator<Item = StateID> + 'a {
-    (0..=u8::MAX)
+    dfa.byte_classes()
+        .representatives(..=u8::MAX)
+        .filter_map(|unit| unit.as_u8())
         .map(move |byte| dfa.next_state(state, byte))
         .chain(iter::once(dfa.next_eoi_state(state)))
 }
@@ -18,6 +18,9 @@ use crate::leaf::{Leaf, LeafId};
 
 mod dfa_util;
 mod export;
+mod profile;
+
+pub use profile::Profile;
 
 /// A configuration used to construct a graph
 #[derive(Debug)]
@@ -59,6 +62,9 @@ impl StateType {
     }
 }
 
+/// The maximum number of bytes compared at once when matching a [Graph::linear_chain].
+pub const MAX_CHAIN_LEN: usize = 16;
+
 /// This type uniquely identifies the state of the Logos state machine.
 /// It is an index into the `states` field of the [Graph] struct.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -78,6 +84,11 @@ impl State {
     pub fn snake_case(&self) -> String {
         format!("{self}")
     }
+
+    /// Index of the state in the graph, from `0` to the number of states.
+    pub fn index(&self) -> usize {
+        self.0
+    }
 }
 
 /// This struct includes all information that should be attached to [State] but does not uniquely
@@ -215,6 +226,14 @@ impl ByteClass {
         self.ranges.push(byte..=byte);
     }
 
+    /// Returns the byte matched by this class if it matches exactly one byte.
+    pub fn single_byte(&self) -> Option<u8> {
+        match self.ranges.as_slice() {
+            [range] if range.start() == range.end() => Some(*range.start()),
+            _ => None,
+        }
+    }
+
     pub fn to_table(&self) -> [bool; 256] {
         let mut table_bits = [false; 256];
         for range in self.ranges.iter() {
@@ -378,6 +397,180 @@ impl Graph {
         &self.dfa
     }
 
+    /// Follow the linear chain of states starting at `state`, if there is one. A state is part
+    /// of a chain when its only transition consumes a single byte, so a literal like `continue`
+    /// becomes a chain of 8 states. States after the first one must also have no context, so
+    /// that the whole chain can be matched at once by comparing several bytes.
+    ///
+    /// Returns the bytes of the chain, at most [MAX_CHAIN_LEN] of them, and the state reached
+    /// after reading them. Chains shorter than 2 bytes are not returned.
+    pub fn linear_chain(&self, state: State) -> Option<(Vec<u8>, State)> {
+        let mut bytes = Vec::new();
+        let mut current = state;
+        while bytes.len() < MAX_CHAIN_LEN {
+            let state_data = self.get_state(current);
+            if current != state && state_data.state_type != StateType::default() {
+                break;
+            }
+            let [(byte_class, next)] = state_data.normal.as_slice() else {
+                break;
+            };
+            let Some(byte) = byte_class.single_byte() else {
+                break;
+            };
+            if *next == current {
+                break;
+            }
+            bytes.push(byte);
+            current = *next;
+        }
+
+        match bytes.len() {
+            0 | 1 => None,
+            _ => Some((bytes, current)),
+        }
+    }
+
+    /// For every state, indexed by [State::index], whether its [linear chain](Self::linear_chain)
+    /// should be compared at once. States that can only be entered from the previous state of
+    /// a chain are skipped: they are either jumped over by the comparison made at the start of
+    /// the chain, or stepped through after it failed, in which case comparing again would fail
+    /// too.
+    pub fn chain_heads(&self) -> Vec<bool> {
+        let mut parents = vec![Vec::new(); self.states.len()];
+        for (index, state_data) in self.states.iter().enumerate() {
+            let children = state_data.normal.iter().map(|(_, next)| next);
+            for next in children.chain(&state_data.eoi) {
+                parents[next.0].push(index);
+            }
+        }
+
+        let chains = self
+            .iter_states()
+            .map(|state| self.linear_chain(state))
+            .collect::<Vec<_>>();
+        let inside_chain = |state: State| {
+            let [parent] = parents[state.0].as_slice() else {
+                return false;
+            };
+            let parent_data = &self.states[*parent];
+            state != self.root
+                && *parent != state.0
+                && self.states[state.0].state_type == StateType::default()
+                && matches!(
+                    parent_data.normal.as_slice(),
+                    [(byte_class, next)] if *next == state && byte_class.single_byte().is_some()
+                )
+        };
+
+        let mut heads = vec![false; self.states.len()];
+        for start in self.iter_states() {
+            if chains[start.0].is_none() || inside_chain(start) {
+                continue;
+            }
+
+            // Chains longer than MAX_CHAIN_LEN continue from the end of the previous comparison
+            let mut current = start;
+            while let Some((_, end)) = &chains[current.0] {
+                if std::mem::replace(&mut heads[current.0], true) || !inside_chain(*end) {
+                    break;
+                }
+                current = *end;
+            }
+        }
+
+        heads
+    }
+
+    /// For every state, indexed by [State::index], the longest token that can still be matched
+    /// from it, according to the `max_len` of the leaves reachable from the state. This is
+    /// `None` if any of those leaves has no limit, or if no leaf is reachable at all.
+    pub fn max_token_lens(&self) -> Vec<Option<usize>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Reach {
+            Nothing,
+            Bounded(usize),
+            Unbounded,
+        }
+
+        fn join(a: Reach, b: Reach) -> Reach {
+            match (a, b) {
+                (Reach::Nothing, other) | (other, Reach::Nothing) => other,
+                (Reach::Bounded(a), Reach::Bounded(b)) => Reach::Bounded(a.max(b)),
+                _ => Reach::Unbounded,
+            }
+        }
+
+        let mut reach = self
+            .states
+            .iter()
+            .map(|state_data| match state_data.state_type.early_or_accept() {
+                Some(leaf_id) => match self.leaves[leaf_id.0].max_len {
+                    Some(max_len) => Reach::Bounded(max_len),
+                    None => Reach::Unbounded,
+                },
+                None => Reach::Nothing,
+            })
+            .collect::<Vec<_>>();
+
+        // Propagate backwards along the edges until nothing changes
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (index, state_data) in self.states.iter().enumerate() {
+                let children = state_data.normal.iter().map(|(_, next)| next);
+                let joined = children
+                    .chain(&state_data.eoi)
+                    .fold(reach[index], |acc, next| join(acc, reach[next.0]));
+                if joined != reach[index] {
+                    reach[index] = joined;
+                    changed = true;
+                }
+            }
+        }
+
+        reach
+            .into_iter()
+            .map(|reach| match reach {
+                Reach::Bounded(max_len) => Some(max_len),
+                Reach::Nothing | Reach::Unbounded => None,
+            })
+            .collect()
+    }
+
+    /// For every leaf, the number of states from which it can still be matched. States shared
+    /// by several leaves are counted for each of them.
+    pub fn states_per_leaf(&self) -> Vec<usize> {
+        let mut parents = vec![Vec::new(); self.states.len()];
+        for (index, state_data) in self.states.iter().enumerate() {
+            let children = state_data.normal.iter().map(|(_, next)| next);
+            for next in children.chain(&state_data.eoi) {
+                parents[next.0].push(index);
+            }
+        }
+
+        (0..self.leaves.len())
+            .map(|leaf_id| {
+                let mut seen = vec![false; self.states.len()];
+                let mut stack = self
+                    .states
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, state_data)| {
+                        state_data.state_type.early_or_accept() == Some(LeafId(leaf_id))
+                    })
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>();
+                while let Some(index) = stack.pop() {
+                    if !std::mem::replace(&mut seen[index], true) {
+                        stack.extend(&parents[index]);
+                    }
+                }
+                seen.into_iter().filter(|&seen| seen).count()
+            })
+            .collect()
+    }
+
     /// Iterate over all the disambiguation errors encountered while generating this graph
     pub fn errors<'b>(&'b self) -> impl Iterator<Item = &'b GraphError> + 'b {
         self.errors.iter()
@@ -394,6 +587,9 @@ impl Graph {
             .map(|leaf| leaf.pattern.hir())
             .collect::<Vec<_>>();
 
+        // Unicode classes aren't cached between patterns: regex-automata compiles each one
+        // into its own UTF-8 automaton and offers no way to reuse a compiled piece. Only the
+        // byte classes of the DFA below are used to keep them cheap.
         let nfa_config = NFA::config().shrink(true).utf8(config.utf8_mode);
         let nfa = NFA::compiler()
             .configure(nfa_config)
@@ -404,9 +600,10 @@ impl Graph {
 
         let dfa_config = DFA::config()
             .accelerate(false)
-            // Turning byte classes on makes compilation go faster but makes the DFA
-            // representation harder to interpret
-            .byte_classes(!cfg!(feature = "debug"))
+            // Byte classes group together the bytes that no state tells apart. Large Unicode
+            // classes such as `\p{XID_Continue}` only use about a hundred of them, which keeps
+            // the transition table small and lets us build the graph one class at a time.
+            .byte_classes(true)
             // I wasn't able to see a performance difference with this on, but it did
             // make compiling the dfa in a large project take ~15 sec, so leaving it off
             .minimize(false)
@@ -449,6 +646,14 @@ impl Graph {
         graph.root = dfa_lookup[&start_id];
         graph.states = vec![StateData::new(); dfa_lookup.len()];
 
+        // Only one byte of each byte class needs to be looked up, since all bytes of a class
+        // lead to the same state
+        let byte_classes = *graph.dfa.byte_classes();
+        let representatives = byte_classes
+            .representatives(..=u8::MAX)
+            .filter_map(|unit| unit.as_u8())
+            .collect::<Vec<_>>();
+
         // Now, for each state, construct its edges and determine which leaves it matches
         for (dfa_id, state_id) in dfa_lookup.iter() {
             let dfa_id = *dfa_id;
@@ -460,21 +665,25 @@ impl Graph {
                     .errors
                     .push(GraphError::Disambiguation(ambiguous_leaves)),
             }
-            let mut result: HashMap<State, ByteClass> = HashMap::new();
-            for input_byte in u8::MIN..=u8::MAX {
+            let mut class_states = vec![None; byte_classes.alphabet_len()];
+            for &input_byte in &representatives {
                 let next_id = graph.dfa.next_state(dfa_id, input_byte);
 
                 // Don't need to account for the dead state
-                if next_id.as_usize() == 0 {
-                    continue;
+                if next_id.as_usize() != 0 {
+                    class_states[byte_classes.get(input_byte) as usize] =
+                        Some(dfa_lookup[&next_id]);
                 }
+            }
 
-                let next_state = dfa_lookup[&next_id];
-
-                result
-                    .entry(next_state)
-                    .or_insert(ByteClass::new())
-                    .add_byte(input_byte);
+            let mut result: HashMap<State, ByteClass> = HashMap::new();
+            for input_byte in u8::MIN..=u8::MAX {
+                if let Some(next_state) = class_states[byte_classes.get(input_byte) as usize] {
+                    result
+                        .entry(next_state)
+                        .or_insert(ByteClass::new())
+                        .add_byte(input_byte);
+                }
             }
 
             state_data.set_normal_edges(result);
@@ -0,0 +1,108 @@
+use super::{ByteClass, Comparisons, Graph, State, StateData};
+
+/// Counts of the bytes read in each state of a [Graph] while lexing a sample input, used to
+/// pick how each state tests the next byte.
+#[derive(Debug)]
+pub struct Profile {
+    /// For each state, the number of times each byte was read in it, whether it led to another
+    /// state or ended the token
+    counts: Vec<[u64; 256]>,
+}
+
+impl Profile {
+    /// Number of times each byte was read in `state`.
+    pub fn byte_counts(&self, state: State) -> &[u64; 256] {
+        &self.counts[state.0]
+    }
+}
+
+impl ByteClass {
+    /// Whether `byte` is part of this class.
+    pub fn contains(&self, byte: u8) -> bool {
+        self.ranges.iter().any(|range| range.contains(&byte))
+    }
+
+    /// Number of times a byte of this class was read, according to `counts`.
+    pub fn count(&self, counts: &[u64; 256]) -> u64 {
+        self.ranges
+            .iter()
+            .flat_map(|range| range.clone())
+            .map(|byte| counts[byte as usize])
+            .sum()
+    }
+}
+
+impl Comparisons {
+    /// Whether `byte` passes these comparisons.
+    pub fn contains(&self, byte: u8) -> bool {
+        self.range.contains(&byte) && !self.except.contains(&byte)
+    }
+
+    /// Number of times a byte passing these comparisons was read, according to `counts`.
+    pub fn count(&self, counts: &[u64; 256]) -> u64 {
+        self.range
+            .clone()
+            .filter(|&byte| !self.except.contains(&byte))
+            .map(|byte| counts[byte as usize])
+            .sum()
+    }
+}
+
+impl Graph {
+    /// Lex `corpus` with this graph and count the bytes read in each state.
+    ///
+    /// Tokens are matched the same way the generated code does, except that callbacks are not
+    /// run. Bytes that can't start a token are skipped one at a time.
+    pub fn profile(&self, corpus: &[u8]) -> Profile {
+        let mut counts = vec![[0; 256]; self.states.len()];
+
+        let mut start = 0;
+        while start < corpus.len() {
+            let mut state = self.root;
+            let mut offset = start;
+            let mut end = None;
+
+            loop {
+                let state_data = self.get_state(state);
+                if state_data.state_type.early.is_some() {
+                    end = Some(offset);
+                } else if state_data.state_type.accept.is_some() {
+                    // Accepting states are reached one byte after the end of the token
+                    end = Some(offset - 1);
+                }
+
+                match corpus.get(offset) {
+                    Some(&byte) => {
+                        counts[state.0][byte as usize] += 1;
+                        match next_state(state_data, byte) {
+                            Some(next) => state = next,
+                            None => break,
+                        }
+                    }
+                    None if offset == corpus.len() => match state_data.eoi {
+                        Some(next) => state = next,
+                        None => break,
+                    },
+                    None => break,
+                }
+                offset += 1;
+            }
+
+            start = match end {
+                Some(end) if end > start => end,
+                _ => start + 1,
+            };
+        }
+
+        Profile { counts }
+    }
+}
+
+/// The state reached by reading `byte` in a state, if any.
+fn next_state(state_data: &StateData, byte: u8) -> Option<State> {
+    state_data
+        .normal
+        .iter()
+        .find(|(byte_class, _)| byte_class.contains(byte))
+        .map(|(_, next)| *next)
+}
@@ -59,6 +59,8 @@ pub struct Leaf {
     pub priority: usize,
     pub kind: VariantKind,
     pub callback: Option<Callback>,
+    /// Longest token this leaf may match, from `max_len` or `#[logos(max_token_len)]`
+    pub max_len: Option<usize>,
 }
 
 impl Leaf {
@@ -69,6 +71,7 @@ impl Leaf {
             priority: 0,
             kind: VariantKind::Skip,
             callback: None,
+            max_len: None,
         }
     }
 
@@ -83,6 +86,10 @@ impl Leaf {
     pub fn priority(self, priority: usize) -> Self {
         Self { priority, ..self }
     }
+
+    pub fn max_len(self, max_len: Option<usize>) -> Self {
+        Self { max_len, ..self }
+    }
 }
 
 impl Display for Leaf {
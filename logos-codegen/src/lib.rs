@@ -12,8 +12,10 @@ mod error;
 mod generator;
 mod graph;
 mod leaf;
+mod owned;
 mod parser;
 mod pattern;
+mod report;
 mod util;
 
 #[macro_use]
@@ -23,7 +25,7 @@ mod macros;
 use std::error::Error;
 use std::ffi::OsStr;
 use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use error::Errors;
 use generator::Generator;
@@ -32,6 +34,7 @@ use leaf::Leaf;
 use parser::Parser;
 use pattern::Pattern;
 use quote::ToTokens;
+use report::Report;
 
 use proc_macro2::{TokenStream, TokenTree};
 use quote::quote;
@@ -42,6 +45,7 @@ use syn::{Fields, ItemEnum};
 use crate::graph::Config;
 use crate::leaf::VariantKind;
 use crate::parser::{Definition, ErrorType, Subpatterns};
+use crate::util::MaybeVoid;
 
 const LOGOS_ATTR: &str = "logos";
 const ERROR_ATTR: &str = "error";
@@ -59,7 +63,7 @@ pub fn generate(input: TokenStream) -> TokenStream {
 
     let mut parser = Parser::default();
 
-    for param in item.generics.params {
+    for param in mem::take(&mut item.generics.params) {
         parser.parse_generic(param);
     }
 
@@ -67,6 +71,8 @@ pub fn generate(input: TokenStream) -> TokenStream {
         parser.try_parse_logos(attr);
     }
 
+    parser.infer_source_lifetime();
+
     debug!("Iterating through subpatterns and skips");
 
     let utf8_mode = parser
@@ -77,6 +83,7 @@ pub fn generate(input: TokenStream) -> TokenStream {
     let config = Config { utf8_mode };
     let subpatterns = Subpatterns::new(&parser.subpatterns, utf8_mode, &mut parser.errors);
 
+    let max_token_len = parser.max_token_len;
     let mut pats = Vec::new();
 
     for skip in mem::take(&mut parser.skips) {
@@ -107,7 +114,8 @@ pub fn generate(input: TokenStream) -> TokenStream {
         pats.push(
             Leaf::new(skip.literal.span(), pattern)
                 .priority(skip.priority.unwrap_or(default_priority))
-                .callback(skip.callback),
+                .callback(skip.callback)
+                .max_len(skip.max_len.or(max_token_len)),
         );
     }
 
@@ -203,7 +211,8 @@ pub fn generate(input: TokenStream) -> TokenStream {
                         Leaf::new(definition.literal.span(), pattern)
                             .variant_kind(var_kind.clone())
                             .priority(definition.priority.unwrap_or(literal_len * 2))
-                            .callback(definition.callback),
+                            .callback(definition.callback)
+                            .max_len(definition.max_len.or(max_token_len)),
                     );
                 }
                 REGEX_ATTR => {
@@ -245,7 +254,8 @@ pub fn generate(input: TokenStream) -> TokenStream {
                         Leaf::new(definition.literal.span(), pattern)
                             .variant_kind(var_kind.clone())
                             .priority(definition.priority.unwrap_or(default_priority))
-                            .callback(definition.callback),
+                            .callback(definition.callback)
+                            .max_len(definition.max_len.or(max_token_len)),
                     );
                 }
                 _ => (),
@@ -253,13 +263,18 @@ pub fn generate(input: TokenStream) -> TokenStream {
         }
     }
 
+    for leaf in &pats {
+        max_len_check(leaf, &mut parser);
+    }
+
     debug!("Parsing additional options (extras, utf8, ...)");
 
     let ErrorType {
         ty: error_type,
         callback: error_callback,
     } = parser.error_type.take().unwrap_or_default();
-    let extras = parser.extras.take();
+    let extras = parser.take_extras();
+    let warning = parser.warning.take();
     let non_utf8_pats = pats
         .iter()
         .filter(|leaf| !leaf.pattern.hir().properties().is_utf8())
@@ -274,21 +289,110 @@ pub fn generate(input: TokenStream) -> TokenStream {
         }
     };
 
-    let source = match utf8_mode {
-        true => quote!(::core::primitive::str),
-        false => quote!([::core::primitive::u8]),
-    };
     let logos_path = parser
         .logos_path
         .take()
         .unwrap_or_else(|| parse_quote!(::logos));
+    let source = match utf8_mode {
+        true => quote!(::core::primitive::str),
+        false => quote!([::core::primitive::u8]),
+    };
 
     let generics = parser.generics();
     let this = quote!(#name #generics);
     let lt_bounds = parser.lifetime_bounds();
     let src_lt = parser.source_lifetime();
 
-    let impl_logos = |body| {
+    let source = match parser.padded {
+        true => quote!(#logos_path::source::Padded<#src_lt, #source>),
+        false => source,
+    };
+
+    let owned = match parser.owned.take() {
+        Some(owned) => owned::generate(
+            &item,
+            &owned,
+            &this,
+            &lt_bounds,
+            &src_lt,
+            &logos_path,
+            &mut parser.errors,
+        ),
+        None => TokenStream::new(),
+    };
+
+    let impl_token_kind = parser.kind.then(|| {
+        let kinds = item.variants.iter().enumerate().map(|(kind, variant)| {
+            let ident = &variant.ident;
+            let kind = kind as u16;
+
+            match variant.fields {
+                Fields::Unit => quote!(Self::#ident => #kind,),
+                Fields::Unnamed(_) => quote!(Self::#ident(..) => #kind,),
+                Fields::Named(_) => quote!(Self::#ident { .. } => #kind,),
+            }
+        });
+        let units = item
+            .variants
+            .iter()
+            .enumerate()
+            .filter(|(_, variant)| matches!(variant.fields, Fields::Unit))
+            .map(|(kind, variant)| {
+                let ident = &variant.ident;
+                let kind = kind as u16;
+
+                quote!(#kind => ::core::option::Option::Some(Self::#ident),)
+            });
+
+        quote! {
+            #[automatically_derived]
+            impl #lt_bounds #logos_path::TokenKind for #this {
+                fn kind(&self) -> u16 {
+                    match *self {
+                        #(#kinds)*
+                    }
+                }
+
+                fn from_kind(kind: u16) -> ::core::option::Option<Self> {
+                    match kind {
+                        #(#units)*
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+        }
+    });
+
+    let (warning_store, impl_warnings) = match warning {
+        MaybeVoid::Some(warning) => (
+            quote!(#logos_path::internal::WarningList<#warning>),
+            quote! {
+                #[automatically_derived]
+                impl #lt_bounds #logos_path::Warnings<#src_lt> for #this {
+                    type Warning = #warning;
+
+                    fn warning_list(store: &Self::WarningStore) -> &#logos_path::internal::WarningList<#warning> {
+                        store
+                    }
+
+                    fn warning_list_mut(store: &mut Self::WarningStore) -> &mut #logos_path::internal::WarningList<#warning> {
+                        store
+                    }
+                }
+            },
+        ),
+        MaybeVoid::Void => (quote!(()), TokenStream::new()),
+    };
+
+    // Generated code never uses unsafe, forbid it in inline callbacks too
+    let forbid_unsafe = parser.forbid_unsafe.then(|| quote!(#[forbid(unsafe_code)]));
+    let forbid_unsafe_const = parser.forbid_unsafe.then(|| {
+        quote!(
+            const FORBID_UNSAFE: bool = true;
+        )
+    });
+
+    let impl_logos = |body, patterns| {
         quote! {
             #[automatically_derived]
             impl #lt_bounds #logos_path::Logos<#src_lt> for #this {
@@ -298,11 +402,24 @@ pub fn generate(input: TokenStream) -> TokenStream {
 
                 type Source = #source;
 
+                type WarningStore = #warning_store;
+
+                #patterns
+
+                #forbid_unsafe_const
+
+                #forbid_unsafe
                 fn lex(lex: &mut #logos_path::Lexer<#src_lt, Self>)
                     -> ::core::option::Option<::core::result::Result<Self, <Self as #logos_path::Logos<#src_lt>>::Error>> {
                     #body
                 }
             }
+
+            #impl_token_kind
+
+            #impl_warnings
+
+            #owned
         }
     };
 
@@ -323,7 +440,7 @@ pub fn generate(input: TokenStream) -> TokenStream {
         Err(msg) => {
             let mut errors = Errors::default();
             errors.err(msg, item_span);
-            return impl_logos(errors.render().unwrap());
+            return impl_logos(errors.render().unwrap(), TokenStream::new());
         }
     };
 
@@ -391,15 +508,77 @@ pub fn generate(input: TokenStream) -> TokenStream {
         }
     }
 
+    debug!("Profiling the graph with the sample corpus");
+
+    // The corpus path is relative to the crate being compiled, like `include_bytes!` paths
+    // are relative to the source file.
+    let profile = parser.profile_corpus.take().and_then(|lit| {
+        let relative = Path::new(&lit.value()).is_relative();
+        let path = match std::env::var_os("CARGO_MANIFEST_DIR") {
+            Some(dir) if relative => Path::new(&dir).join(lit.value()),
+            _ => PathBuf::from(lit.value()),
+        };
+        match std::fs::read(&path) {
+            Ok(corpus) => Some((graph.profile(&corpus), lit, relative)),
+            Err(_) => {
+                parser.err(
+                    format!("Could not read the profile corpus {:?}", lit.value()),
+                    lit.span(),
+                );
+                None
+            }
+        }
+    });
+
+    if parser.const_fn {
+        // `lex_const` can't call callbacks, nor slice the source to build values
+        for leaf in graph.leaves() {
+            if matches!(leaf.kind, VariantKind::Value(..)) || leaf.callback.is_some() {
+                parser.err(
+                    concat!(
+                        "#[logos(const_fn)] only supports unit variants and skips without ",
+                        "callbacks"
+                    ),
+                    leaf.span,
+                );
+            }
+        }
+    }
+
     if let Some(errors) = parser.errors.render() {
-        return impl_logos(errors);
+        return impl_logos(errors, TokenStream::new());
     }
 
+    // Rebuild the lexer when the corpus changes
+    let track_corpus = profile.as_ref().map(|(_, lit, relative)| {
+        let path = match relative {
+            true => quote!(::core::concat!(::core::env!("CARGO_MANIFEST_DIR"), "/", #lit)),
+            false => quote!(#lit),
+        };
+        quote!(
+            const _: &[::core::primitive::u8] = ::core::include_bytes!(#path);
+        )
+    });
+    let profile = profile.map(|(profile, _, _)| profile);
+
     debug!("Generating code from graph");
 
+    // `#[logos(codegen = "...")]` takes precedence over the crate features
+    let codegen = parser.codegen.as_ref().map(|lit| lit.value());
     let config = generator::Config {
-        use_state_machine_codegen: cfg!(feature = "state_machine_codegen"),
+        use_state_machine_codegen: match codegen.as_deref() {
+            Some(codegen) => codegen == "state_machine",
+            None => cfg!(feature = "state_machine_codegen"),
+        },
+        use_table_codegen: match codegen.as_deref() {
+            Some(codegen) => codegen == "table",
+            None => cfg!(feature = "table_codegen"),
+        },
+        forbid_unsafe: parser.forbid_unsafe,
+        padded: parser.padded,
+        profile,
     };
+    let on_token = parser.on_token.take();
     let mut generator = Generator::new(
         config,
         name,
@@ -408,10 +587,30 @@ pub fn generate(input: TokenStream) -> TokenStream {
         &lt_bounds,
         &graph,
         &error_callback,
+        &on_token,
     );
 
     let body = generator.generate();
-    impl_logos(quote! {
+    let const_fn = parser
+        .const_fn
+        .then(|| generator.generate_const_fn(utf8_mode));
+    let use_token_event = on_token
+        .as_ref()
+        .map(|_| quote!(use #logos_path::TokenEvent as _TokenEvent;));
+    let use_token_too_long = graph
+        .leaves()
+        .iter()
+        .any(|leaf| leaf.max_len.is_some())
+        .then(|| quote!(use #logos_path::TokenTooLong as _TokenTooLong;));
+    let use_read_byte = (parser.padded && !parser.forbid_unsafe)
+        .then(|| quote!(use #logos_path::__read_byte as _read_byte;));
+    let patterns = graph
+        .leaves()
+        .iter()
+        .map(|leaf| leaf.pattern.source_value());
+    let patterns = quote!(const PATTERNS: &'static [&'static str] = &[#(#patterns),*];);
+    let mut tokens = impl_logos(
+        quote! {
         use #logos_path::internal::{
             LexerInternal,
             CallbackRetVal,
@@ -423,9 +622,23 @@ pub fn generate(input: TokenStream) -> TokenStream {
         use ::core::option::Option as _Option;
         use #logos_path::Lexer as _Lexer;
         use #logos_path::Logos;
+        #use_token_event
+        #use_token_too_long
+        #use_read_byte
+        #track_corpus
 
         #body
-    })
+        },
+        patterns,
+    );
+    tokens.extend(const_fn);
+
+    if let Some(span) = parser.report {
+        let report = Report::new(name.to_string(), &graph, generator.lut_count(), &tokens);
+        tokens.extend(report.warning(span));
+    }
+
+    tokens
 }
 
 fn greedy_dotall_check(definition: &Definition, pattern: &Pattern, parser: &mut Parser) {
@@ -446,6 +659,25 @@ fn greedy_dotall_check(definition: &Definition, pattern: &Pattern, parser: &mut
     }
 }
 
+fn max_len_check(leaf: &Leaf, parser: &mut Parser) {
+    let Some(max_len) = leaf.max_len else {
+        return;
+    };
+    match leaf.pattern.hir().properties().minimum_len() {
+        Some(min_len) if min_len > max_len => {
+            parser.err(
+                format!(
+                    "This pattern only matches tokens of at least {min_len} bytes, which is \
+                    over its length limit of {max_len} bytes, so it can never match. \
+                    Raise the limit with max_len = <integer>"
+                ),
+                leaf.span,
+            );
+        }
+        _ => (),
+    }
+}
+
 /// Strip all logos attributes from the given enum, allowing it to be used in code without `logos-derive` present.
 pub fn strip_attributes(input: TokenStream) -> TokenStream {
     let mut item: ItemEnum = syn::parse2(input).expect("Logos can only be derived for enums");
@@ -486,7 +718,7 @@ fn strip_attrs_from_vec(attrs: &mut Vec<syn::Attribute>) {
     attrs.retain(|attr| !is_logos_attr(attr))
 }
 
-fn is_logos_attr(attr: &syn::Attribute) -> bool {
+pub(crate) fn is_logos_attr(attr: &syn::Attribute) -> bool {
     attr.path().is_ident(LOGOS_ATTR)
         || attr.path().is_ident(TOKEN_ATTR)
         || attr.path().is_ident(REGEX_ATTR)
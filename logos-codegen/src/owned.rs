@@ -0,0 +1,280 @@
+//! Generation of the owned twin of a token enum, see `#[logos(owned = ...)]`.
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Attribute, Fields, Ident, ItemEnum, Lifetime, Meta, Path, Token, Type};
+
+use crate::error::Errors;
+use crate::{is_logos_attr, LOGOS_ATTR};
+
+/// Definition of the owned twin, from `#[logos(owned(TokenOwned, derive(...)))]`.
+pub struct OwnedDef {
+    pub ident: Ident,
+    pub derives: TokenStream,
+}
+
+impl OwnedDef {
+    pub fn new(ident: Ident) -> Self {
+        OwnedDef {
+            ident,
+            derives: TokenStream::new(),
+        }
+    }
+}
+
+/// How a field borrowing from the source is turned into an owned value.
+enum Conversion {
+    /// `&str` into `String`
+    Str,
+    /// `&[u8]` into `Vec<u8>`
+    Bytes,
+    /// Convert each element of a tuple
+    Tuple(Vec<Conversion>),
+    /// Field doesn't borrow from the source, move it as is
+    Keep,
+}
+
+impl Conversion {
+    fn apply(&self, value: TokenStream, logos_path: &TokenStream) -> TokenStream {
+        match self {
+            Conversion::Str => quote!(#logos_path::internal::String::from(#value)),
+            Conversion::Bytes => quote!(#logos_path::internal::Vec::from(#value)),
+            Conversion::Tuple(elems) => {
+                let names = (0..elems.len())
+                    .map(|i| format_ident!("elem{}", i))
+                    .collect::<Vec<_>>();
+                let converted = elems
+                    .iter()
+                    .zip(&names)
+                    .map(|(conversion, name)| conversion.apply(quote!(#name), logos_path));
+
+                quote!({
+                    let (#(#names,)*) = #value;
+                    (#(#converted,)*)
+                })
+            }
+            Conversion::Keep => value,
+        }
+    }
+}
+
+/// Generate the owned twin `owned` of the token enum `item`, along with the
+/// conversions from the token type.
+///
+/// Field types of `item` are expected to have their lifetimes and type
+/// parameters already resolved by the `Parser`.
+pub fn generate(
+    item: &ItemEnum,
+    def: &OwnedDef,
+    this: &TokenStream,
+    lt_bounds: &TokenStream,
+    src_lt: &TokenStream,
+    logos_path: &TokenStream,
+    errors: &mut Errors,
+) -> TokenStream {
+    let src_lt = match syn::parse2::<Lifetime>(src_lt.clone()) {
+        Ok(lt) => lt,
+        Err(err) => {
+            errors.err(err.to_string(), err.span());
+            return TokenStream::new();
+        }
+    };
+
+    let owned = &def.ident;
+    let name = &item.ident;
+    let vis = &item.vis;
+    let attrs = item.attrs.iter().filter_map(owned_attr);
+    let derives = match def.derives.is_empty() {
+        true => None,
+        false => {
+            let derives = &def.derives;
+            Some(quote!(#[derive(#derives)]))
+        }
+    };
+
+    let mut variants = Vec::new();
+    let mut arms = Vec::new();
+    let mut failed = false;
+
+    for variant in &item.variants {
+        let ident = &variant.ident;
+        let variant_attrs = variant.attrs.iter().filter(|attr| !is_logos_attr(attr));
+
+        match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+
+                let Some((owned_ty, conversion)) = owned_type(ty, &src_lt, logos_path, errors)
+                else {
+                    failed = true;
+                    continue;
+                };
+                let value = conversion.apply(quote!(value), logos_path);
+
+                variants.push(quote!(#(#variant_attrs)* #ident(#owned_ty)));
+                arms.push(quote!(#name::#ident(value) => #owned::#ident(#value)));
+            }
+            Fields::Unit => {
+                variants.push(quote!(#(#variant_attrs)* #ident));
+                arms.push(quote!(#name::#ident => #owned::#ident));
+            }
+            // Other kinds of variants are already reported as errors
+            _ => failed = true,
+        }
+    }
+
+    if failed {
+        return TokenStream::new();
+    }
+
+    let doc = format!(" Owned version of [`{name}`], see [`{name}::into_owned`].");
+
+    quote! {
+        #[doc = #doc]
+        #derives
+        #(#attrs)*
+        #vis enum #owned {
+            #(#variants,)*
+        }
+
+        impl #lt_bounds #this {
+            /// Convert this token into a token that doesn't borrow from the source.
+            #vis fn into_owned(self) -> #owned {
+                match self {
+                    #(#arms,)*
+                }
+            }
+
+            /// Clone this token into a token that doesn't borrow from the source.
+            #vis fn to_owned_token(&self) -> #owned
+            where
+                // Higher-ranked so that the bound isn't checked eagerly on
+                // token types that don't implement `Clone`.
+                for<'__logos> Self: ::core::clone::Clone,
+            {
+                ::core::clone::Clone::clone(self).into_owned()
+            }
+        }
+
+        impl #lt_bounds ::core::convert::From<#this> for #owned {
+            fn from(token: #this) -> Self {
+                token.into_owned()
+            }
+        }
+    }
+}
+
+/// Find the owned type of a field type, and how to convert to it.
+fn owned_type(
+    ty: &Type,
+    src_lt: &Lifetime,
+    logos_path: &TokenStream,
+    errors: &mut Errors,
+) -> Option<(Type, Conversion)> {
+    match ty {
+        Type::Reference(reference)
+            if reference.mutability.is_none() && reference.lifetime.as_ref() == Some(src_lt) =>
+        {
+            match &*reference.elem {
+                Type::Path(path) if path.qself.is_none() && path.path.is_ident("str") => Some((
+                    syn::parse_quote!(#logos_path::internal::String),
+                    Conversion::Str,
+                )),
+                Type::Slice(slice) if matches!(&*slice.elem, Type::Path(p) if p.path.is_ident("u8")) => {
+                    Some((
+                        syn::parse_quote!(#logos_path::internal::Vec<::core::primitive::u8>),
+                        Conversion::Bytes,
+                    ))
+                }
+                _ => unsupported(ty, errors),
+            }
+        }
+        Type::Tuple(tuple) => {
+            let mut owned = tuple.clone();
+            let mut conversions = Vec::new();
+
+            for elem in owned.elems.iter_mut() {
+                let (owned_elem, conversion) = owned_type(elem, src_lt, logos_path, errors)?;
+
+                *elem = owned_elem;
+                conversions.push(conversion);
+            }
+
+            match conversions.iter().all(|c| matches!(c, Conversion::Keep)) {
+                true => Some((ty.clone(), Conversion::Keep)),
+                false => Some((Type::Tuple(owned), Conversion::Tuple(conversions))),
+            }
+        }
+        Type::Paren(paren) => owned_type(&paren.elem, src_lt, logos_path, errors),
+        Type::Group(group) => owned_type(&group.elem, src_lt, logos_path, errors),
+        _ if has_lifetime(ty.to_token_stream()) => unsupported(ty, errors),
+        _ => Some((ty.clone(), Conversion::Keep)),
+    }
+}
+
+fn unsupported<T>(ty: &Type, errors: &mut Errors) -> Option<T> {
+    errors.err(
+        concat!(
+            "#[logos(owned)] can only convert fields of type `&str`, `&[u8]`, ",
+            "or tuples of those, borrowing from the source.\n",
+            "\n",
+            "Other field types must not borrow anything."
+        ),
+        ty.span(),
+    );
+    None
+}
+
+/// Check for any lifetime other than `'static`.
+fn has_lifetime(tokens: TokenStream) -> bool {
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(tt) = tokens.next() {
+        match tt {
+            TokenTree::Punct(punct)
+                if punct.as_char() == '\''
+                    && !matches!(tokens.peek(), Some(TokenTree::Ident(ident)) if ident == "static") =>
+            {
+                return true;
+            }
+            TokenTree::Group(group) if has_lifetime(group.stream()) => return true,
+            _ => (),
+        }
+    }
+
+    false
+}
+
+/// Attributes of the token enum that also apply to the owned twin.
+///
+/// Docs are skipped, and `Logos` and `Copy` are removed from derives.
+fn owned_attr(attr: &Attribute) -> Option<TokenStream> {
+    if attr.path().is_ident(LOGOS_ATTR) || attr.path().is_ident("doc") {
+        return None;
+    }
+
+    let Meta::List(list) = &attr.meta else {
+        return Some(attr.to_token_stream());
+    };
+
+    if !list.path.is_ident("derive") {
+        return Some(attr.to_token_stream());
+    }
+
+    let derives = list
+        .parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+        .ok()?
+        .into_iter()
+        .filter(|path| {
+            path.segments
+                .last()
+                .is_some_and(|segment| segment.ident != "Logos" && segment.ident != "Copy")
+        })
+        .collect::<Vec<_>>();
+
+    let span = attr.span();
+
+    Some(quote::quote_spanned!(span => #[derive(#(#derives),*)]))
+}
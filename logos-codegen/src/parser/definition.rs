@@ -12,6 +12,7 @@ pub struct Definition {
     pub priority: Option<usize>,
     pub callback: Option<Callback>,
     pub allow_greedy: Option<bool>,
+    pub max_len: Option<usize>,
     pub ignore_flags: IgnoreFlags,
 }
 
@@ -69,6 +70,7 @@ impl Definition {
             priority: None,
             callback: None,
             allow_greedy: None,
+            max_len: None,
             ignore_flags: IgnoreFlags::default(),
         }
     }
@@ -135,13 +137,29 @@ impl Definition {
             ("allow_greedy", _) => {
                 parser.err("Expected: allow_greedy = ...", name.span());
             }
+            ("max_len", NestedValue::Assign(tokens)) => {
+                let max_len = match tokens.to_string().parse() {
+                    Ok(max_len) if max_len > 0 => max_len,
+                    _ => {
+                        parser.err("Expected a positive integer", tokens.span());
+                        return;
+                    }
+                };
+
+                if self.max_len.replace(max_len).is_some() {
+                    parser.err("Resetting previously set max_len", tokens.span());
+                }
+            }
+            ("max_len", _) => {
+                parser.err("Expected: max_len = <integer>", name.span());
+            }
             (unknown, _) => {
                 parser.err(
                     format!(
                         "\
                         Unknown nested attribute: {unknown}\n\
                         \n\
-                        Expected one of: priority, callback, ignore, allow_greedy\
+                        Expected one of: priority, callback, ignore, allow_greedy, max_len\
                         "
                     ),
                     name.span(),
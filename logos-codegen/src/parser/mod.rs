@@ -1,11 +1,12 @@
 use proc_macro2::{Span, TokenStream, TokenTree};
-use quote::quote;
+use quote::{quote, quote_spanned};
 use std::borrow::Cow;
 use syn::spanned::Spanned;
-use syn::{Attribute, GenericParam, Ident, Lit, LitBool, Meta, Type};
+use syn::{Attribute, GenericParam, Ident, Lit, LitBool, LitStr, Meta, Type};
 
 use crate::error::Errors;
 use crate::leaf::{Callback, InlineCallback};
+use crate::owned::OwnedDef;
 use crate::util::{expect_punct, MaybeVoid};
 use crate::LOGOS_ATTR;
 
@@ -29,10 +30,22 @@ pub struct Parser {
     pub utf8_mode: Option<LitBool>,
     pub skips: Vec<Definition>,
     pub extras: MaybeVoid,
+    pub warning: MaybeVoid,
     pub subpatterns: Vec<(Ident, Literal)>,
     pub error_type: Option<ErrorType>,
     pub logos_path: Option<TokenStream>,
     pub export_path: Option<String>,
+    pub owned: Option<OwnedDef>,
+    pub on_token: Option<TokenStream>,
+    pub codegen: Option<LitStr>,
+    pub const_fn: bool,
+    pub kind: bool,
+    pub max_token_len: Option<usize>,
+    pub forbid_unsafe: bool,
+    pub padded: bool,
+    pub profile_corpus: Option<LitStr>,
+    /// Span of the `report` option, which the report is emitted on
+    pub report: Option<Span>,
     types: TypeParams,
 }
 
@@ -63,6 +76,24 @@ impl Parser {
         self.types.source_lifetime(Some(&mut self.errors))
     }
 
+    /// Infer the source lifetime from the lifetimes the extras type doesn't borrow for,
+    /// see [`TypeParams::infer_source_lifetime`]. Must be called before parsing variants.
+    pub fn infer_source_lifetime(&mut self) {
+        if let MaybeVoid::Some(extras) = &self.extras {
+            self.types.infer_source_lifetime(extras);
+        }
+    }
+
+    /// Take the extras type, checking that its lifetimes are declared on the token type.
+    pub fn take_extras(&mut self) -> MaybeVoid {
+        match self.extras.take() {
+            MaybeVoid::Some(extras) => {
+                MaybeVoid::Some(self.types.fix_extras_lifetimes(extras, &mut self.errors))
+            }
+            MaybeVoid::Void => MaybeVoid::Void,
+        }
+    }
+
     fn parse_attr(&mut self, attr: &mut Attribute) -> Option<AttributeParser> {
         match &mut attr.meta {
             Meta::List(list) => {
@@ -92,6 +123,26 @@ impl Parser {
         for nested in nested {
             let (name, value) = match nested {
                 Nested::Named(name, value) => (name, value),
+                Nested::Unnamed(tokens) if is_flag(&tokens, "const_fn") => {
+                    self.const_fn = true;
+                    continue;
+                }
+                Nested::Unnamed(tokens) if is_flag(&tokens, "kind") => {
+                    self.kind = true;
+                    continue;
+                }
+                Nested::Unnamed(tokens) if is_flag(&tokens, "forbid_unsafe") => {
+                    self.forbid_unsafe = true;
+                    continue;
+                }
+                Nested::Unnamed(tokens) if is_flag(&tokens, "padded") => {
+                    self.padded = true;
+                    continue;
+                }
+                Nested::Unnamed(tokens) if is_flag(&tokens, "report") => {
+                    self.report = Some(tokens.span());
+                    continue;
+                }
                 Nested::Unexpected(tokens) | Nested::Unnamed(tokens) => {
                     self.err("Invalid nested attribute", tokens.span());
                     continue;
@@ -101,6 +152,34 @@ impl Parser {
             let span = name.span();
 
             match name.to_string().as_str() {
+                "codegen" => match value {
+                    NestedValue::Assign(value) => {
+                        let span = value.span();
+
+                        match syn::parse2::<LitStr>(value) {
+                            Ok(lit)
+                                if matches!(
+                                    lit.value().as_str(),
+                                    "tailcall" | "state_machine" | "table"
+                                ) =>
+                            {
+                                if let Some(previous) = self.codegen.replace(lit) {
+                                    self.err("Codegen can be defined only once", span)
+                                        .err("Previous definition here", previous.span());
+                                }
+                            }
+                            _ => {
+                                self.err(
+                                    r#"Expected one of "tailcall", "state_machine" or "table""#,
+                                    span,
+                                );
+                            }
+                        }
+                    }
+                    _ => {
+                        self.err(r#"Expected: #[logos(codegen = "table")]"#, span);
+                    }
+                },
                 "crate" => match value {
                     NestedValue::Assign(logos_path) => self.logos_path = Some(logos_path),
                     _ => {
@@ -216,6 +295,79 @@ impl Parser {
                         self.err("Expected: #[logos(extras = SomeType)]", span);
                     }
                 },
+                "max_token_len" => match value {
+                    NestedValue::Assign(value) => {
+                        let span = value.span();
+
+                        match value.to_string().parse() {
+                            Ok(max_len) if max_len > 0 => {
+                                if self.max_token_len.replace(max_len).is_some() {
+                                    self.err("Maximum token length can be defined only once", span);
+                                }
+                            }
+                            _ => {
+                                self.err("Expected a positive integer", span);
+                            }
+                        }
+                    }
+                    _ => {
+                        self.err("Expected: #[logos(max_token_len = 1024)]", span);
+                    }
+                },
+                "owned" => {
+                    let owned = match value {
+                        NestedValue::Assign(value) => match syn::parse2::<Ident>(value) {
+                            Ok(ident) => Some(OwnedDef::new(ident)),
+                            Err(e) => {
+                                self.err(e.to_string(), e.span());
+                                None
+                            }
+                        },
+                        NestedValue::Group(value) => self.parse_owned(value, span),
+                        _ => {
+                            self.err(
+                                concat!(
+                                    "Expected: #[logos(owned = TokenOwned)] or ",
+                                    "#[logos(owned(TokenOwned, derive(...)))]"
+                                ),
+                                span,
+                            );
+                            None
+                        }
+                    };
+
+                    if let Some(owned) = owned {
+                        let span = owned.ident.span();
+
+                        if let Some(previous) = self.owned.replace(owned) {
+                            self.err("Owned type can be defined only once", span)
+                                .err("Previous definition here", previous.ident.span());
+                        }
+                    }
+                }
+                "profile_corpus" => match value {
+                    NestedValue::Assign(value) => {
+                        let span = value.span();
+
+                        match syn::parse2::<LitStr>(value) {
+                            Ok(lit) => {
+                                if let Some(previous) = self.profile_corpus.replace(lit) {
+                                    self.err("Profile corpus can be defined only once", span)
+                                        .err("Previous definition here", previous.span());
+                                }
+                            }
+                            Err(_) => {
+                                self.err("Expected a &str", span);
+                            }
+                        }
+                    }
+                    _ => {
+                        self.err(
+                            "Expected: #[logos(profile_corpus = \"path/to/sample.txt\")]",
+                            span,
+                        );
+                    }
+                },
                 "skip" => match value {
                     NestedValue::Literal(lit) => {
                         if let Some(literal) = self.parse_literal(Lit::new(lit)) {
@@ -289,6 +441,32 @@ impl Parser {
                         self.err("Expected: #[logos(utf8 = true)]", span);
                     }
                 },
+                "warning" => match value {
+                    NestedValue::Assign(value) => {
+                        let span = value.span();
+
+                        if let MaybeVoid::Some(previous) = self.warning.replace(value) {
+                            self.err("Warning type can be defined only once", span)
+                                .err("Previous definition here", previous.span());
+                        }
+                    }
+                    _ => {
+                        self.err("Expected: #[logos(warning = SomeType)]", span);
+                    }
+                },
+                "on_token" => match value {
+                    NestedValue::Assign(value) => {
+                        let span = value.span();
+
+                        if let Some(previous) = self.on_token.replace(value) {
+                            self.err("Token hook can be defined only once", span)
+                                .err("Previous definition here", previous.span());
+                        }
+                    }
+                    _ => {
+                        self.err("Expected: #[logos(on_token = path::to::hook)]", span);
+                    }
+                },
                 "lifetime" => match value {
                     NestedValue::Assign(value) => {
                         if let Some(span) = self.types.source_lifetime_span() {
@@ -347,6 +525,15 @@ impl Parser {
                 Nested::Unexpected(tokens) => {
                     self.err("Unexpected token in attribute", tokens.span());
                 }
+                Nested::Unnamed(tokens) if is_flag(&tokens, "intern") => {
+                    let span = tokens.span();
+                    let callback = Callback::Label(quote_spanned!(span => _Lexer::intern));
+
+                    if let Some(previous) = def.callback.replace(callback) {
+                        self.err("Callback has been already set", span)
+                            .err("Previous callback set here", previous.span());
+                    }
+                }
                 Nested::Unnamed(tokens) => match position {
                     0 => def.callback = self.parse_callback(tokens),
                     _ => {
@@ -369,6 +556,49 @@ impl Parser {
         Some(def)
     }
 
+    /// Parse `#[logos(owned(TokenOwned[, derive(...)]))]`
+    fn parse_owned(&mut self, tokens: TokenStream, span: Span) -> Option<OwnedDef> {
+        let mut nested = AttributeParser::new(tokens);
+        let mut owned = match nested.next() {
+            Some(Nested::Unnamed(tokens)) => match syn::parse2::<Ident>(tokens) {
+                Ok(ident) => OwnedDef::new(ident),
+                Err(e) => {
+                    self.err(e.to_string(), e.span());
+                    return None;
+                }
+            },
+            _ => {
+                self.err("Expected #[logos(owned(TokenOwned[, derive(...)]))]", span);
+                return None;
+            }
+        };
+
+        for next in nested {
+            match next {
+                Nested::Named(name, NestedValue::Group(derives)) if name == "derive" => {
+                    owned.derives.extend(derives);
+                }
+                Nested::Named(name, _) => {
+                    self.err(
+                        format!(
+                            "\
+                            Unknown nested attribute: {name}\n\
+                            \n\
+                            Expected: derive(...)\
+                            "
+                        ),
+                        name.span(),
+                    );
+                }
+                Nested::Unnamed(tokens) | Nested::Unexpected(tokens) => {
+                    self.err("Unexpected token in attribute", tokens.span());
+                }
+            }
+        }
+
+        Some(owned)
+    }
+
     fn parse_callback(&mut self, tokens: TokenStream) -> Option<Callback> {
         let span = tokens.span();
         let mut tokens = tokens.into_iter();
@@ -445,3 +675,13 @@ impl Parser {
         self.errors.err(message, span)
     }
 }
+
+/// Check for a lone ident option, such as `intern` or `forbid_unsafe`.
+fn is_flag(tokens: &TokenStream, flag: &str) -> bool {
+    let mut tokens = tokens.clone().into_iter();
+
+    matches!(
+        (tokens.next(), tokens.next()),
+        (Some(TokenTree::Ident(ident)), None) if ident == flag
+    )
+}
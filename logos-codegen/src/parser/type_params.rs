@@ -1,4 +1,4 @@
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Group, Ident, Spacing, Span, TokenStream, TokenTree};
 use quote::quote;
 use syn::spanned::Spanned;
 use syn::{Lifetime, LifetimeParam, Path, Type};
@@ -219,6 +219,79 @@ impl TypeParams {
         quote!(<#(#bounds),*>)
     }
 
+    /// When the source lifetime is implicit and the token type has several lifetimes,
+    /// use the only one that the extras type doesn't borrow for as the source lifetime.
+    ///
+    /// When the token type has no lifetimes, it doesn't borrow from the source, and the
+    /// only lifetime the extras type borrows for becomes the lifetime of the impl.
+    pub fn infer_source_lifetime(&mut self, extras: &TokenStream) {
+        let mut used = Vec::new();
+        visit_lifetimes(extras.clone(), &mut |lt| {
+            if lt.ident != "static" && lt.ident != "_" && !used.contains(&lt) {
+                used.push(lt);
+            }
+        });
+
+        if self.lifetime_params.is_empty()
+            && !matches!(self.source_lifetime, SourceLifetime::Named(_))
+        {
+            if let [lt] = used.as_slice() {
+                self.fresh_lifetime_name = lt.to_string();
+                self.source_lifetime = SourceLifetime::Fresh(lt.span());
+            }
+            return;
+        }
+
+        if !matches!(self.source_lifetime, SourceLifetime::Implicit)
+            || self.lifetime_params.len() < 2
+        {
+            return;
+        }
+
+        let mut candidates = self
+            .lifetime_params
+            .iter()
+            .filter(|ltp| !used.contains(&ltp.lifetime));
+
+        if let (Some(source), None) = (candidates.next(), candidates.next()) {
+            self.source_lifetime = SourceLifetime::Named(source.lifetime.clone());
+        }
+    }
+
+    /// Checks that all lifetimes of the extras type are declared on the token type,
+    /// renaming them to 's when the source lifetime is implicit.
+    pub fn fix_extras_lifetimes(&self, tokens: TokenStream, errors: &mut Errors) -> TokenStream {
+        let implicit = matches!(self.source_lifetime, SourceLifetime::Implicit);
+        let fresh = matches!(self.source_lifetime, SourceLifetime::Fresh(_));
+
+        map_lifetimes(tokens, &mut |lt| {
+            let declared = self.lifetime_params.iter().any(|ltp| ltp.lifetime == lt);
+
+            if lt.ident == "static" || lt.ident == "_" || (declared && !implicit) {
+                return lt;
+            }
+            if fresh && lt.to_string() == self.fresh_lifetime_name {
+                return lt;
+            }
+            if implicit && (declared || lt.ident == "s") {
+                return Lifetime::new("'s", lt.span());
+            }
+
+            errors.err(
+                format!(
+                    "Lifetime `{lt}` of the extras type is not declared on the token type\n\
+                    \n\
+                    Lifetimes borrowed by extras have to be parameters of the token type, \
+                    next to the source lifetime: enum Token<'s, {lt}>. Token types without \
+                    lifetimes can borrow for a single lifetime in their extras instead."
+                ),
+                lt.span(),
+            );
+            // Avoid a second error from rustc about the undeclared lifetime
+            Lifetime::new("'static", lt.span())
+        })
+    }
+
     /// Replaces all lifetimes with 's when source lifetime is implicit for backwards compatibility
     pub fn fix_source_lifetime_implicit(&self, ty: &mut Type) {
         if matches!(&self.source_lifetime, SourceLifetime::Implicit) {
@@ -321,3 +394,46 @@ fn traverse_path(path: &mut Path, f: &mut impl FnMut(&mut Type)) {
         }
     }
 }
+
+/// Calls `f` with every lifetime in `tokens`.
+fn visit_lifetimes(tokens: TokenStream, f: &mut impl FnMut(Lifetime)) {
+    map_lifetimes(tokens, &mut |lt| {
+        f(lt.clone());
+        lt
+    });
+}
+
+/// Replaces every lifetime in `tokens` with the one returned by `f`.
+fn map_lifetimes(tokens: TokenStream, f: &mut impl FnMut(Lifetime) -> Lifetime) -> TokenStream {
+    let mut out = Vec::new();
+    let mut tokens = tokens.into_iter();
+
+    while let Some(tt) = tokens.next() {
+        match tt {
+            TokenTree::Punct(punct)
+                if punct.as_char() == '\'' && punct.spacing() == Spacing::Joint =>
+            {
+                let Some(TokenTree::Ident(name)) = tokens.next() else {
+                    out.push(TokenTree::Punct(punct));
+                    continue;
+                };
+
+                let lt = f(Lifetime {
+                    apostrophe: punct.span(),
+                    ident: name,
+                });
+
+                out.extend(quote!(#lt));
+            }
+            TokenTree::Group(group) => {
+                let mut fixed = Group::new(group.delimiter(), map_lifetimes(group.stream(), f));
+
+                fixed.set_span(group.span());
+                out.push(TokenTree::Group(fixed));
+            }
+            tt => out.push(tt),
+        }
+    }
+
+    out.into_iter().collect()
+}
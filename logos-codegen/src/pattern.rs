@@ -132,6 +132,15 @@ impl Pattern {
     pub fn source(&self) -> &str {
         &self.source
     }
+
+    /// Get the value of the source literal, with non-ASCII bytes of byte strings escaped.
+    pub fn source_value(&self) -> String {
+        match syn::parse_str::<syn::Lit>(&self.source) {
+            Ok(syn::Lit::Str(lit)) => lit.value(),
+            Ok(syn::Lit::ByteStr(lit)) => lit.value().escape_ascii().to_string(),
+            _ => self.source.clone(),
+        }
+    }
 }
 
 impl fmt::Display for Pattern {
@@ -0,0 +1,156 @@
+use std::fmt;
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::quote_spanned;
+
+use crate::graph::Graph;
+
+/// Number of patterns listed by the report, from the one with the most states
+const TOP_PATTERNS: usize = 5;
+
+/// Summary of the size of a generated lexer, emitted as a warning with `#[logos(report)]`.
+pub struct Report<'a> {
+    /// Name of the token type
+    name: String,
+    /// Graph the lexer was generated from
+    graph: &'a Graph,
+    /// Number of lookup tables rendered for fast loops and forks
+    lut_count: usize,
+    /// Number of functions in the generated code, including nested ones
+    function_count: usize,
+    /// Number of token trees in the generated code
+    token_count: usize,
+}
+
+impl<'a> Report<'a> {
+    pub fn new(name: String, graph: &'a Graph, lut_count: usize, tokens: &TokenStream) -> Self {
+        let mut report = Report {
+            name,
+            graph,
+            lut_count,
+            function_count: 0,
+            token_count: 0,
+        };
+        report.count_tokens(tokens.clone());
+        report
+    }
+
+    fn count_tokens(&mut self, tokens: TokenStream) {
+        for tree in tokens {
+            self.token_count += 1;
+            match tree {
+                TokenTree::Group(group) => self.count_tokens(group.stream()),
+                TokenTree::Ident(ident) if ident == "fn" => self.function_count += 1,
+                _ => (),
+            }
+        }
+    }
+
+    /// Emit the report as a compiler warning pointing at `span`. Proc macros can't emit
+    /// warnings on stable, so this goes through the use of a deprecated item.
+    pub fn warning(&self, span: Span) -> TokenStream {
+        let note = format!("\n{self}");
+
+        quote_spanned! {span=>
+            const _: () = {
+                #[deprecated(note = #note)]
+                const LOGOS_REPORT: () = ();
+                LOGOS_REPORT
+            };
+        }
+    }
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Logos report for `{}`:", self.name)?;
+        writeln!(f, "  states:        {}", self.graph.iter_states().count())?;
+        writeln!(f, "  leaves:        {}", self.graph.leaves().len())?;
+        writeln!(f, "  lookup tables: {}", self.lut_count)?;
+        writeln!(f, "  functions:     {}", self.function_count)?;
+        writeln!(f, "  token trees:   {}", self.token_count)?;
+
+        let mut states_per_leaf = self
+            .graph
+            .states_per_leaf()
+            .into_iter()
+            .enumerate()
+            .collect::<Vec<_>>();
+        // Stable sort, so that patterns with as many states stay in declaration order
+        states_per_leaf.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        write!(f, "  patterns with the most states:")?;
+        for (leaf_id, states) in states_per_leaf.into_iter().take(TOP_PATTERNS) {
+            write!(f, "\n    {states:>5}  {}", self.graph.leaves()[leaf_id])?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+    use proc_macro2::Span;
+    use quote::quote;
+
+    use crate::{
+        graph::Config,
+        leaf::{Leaf, VariantKind},
+        pattern::Pattern,
+        util::ToIdent,
+    };
+
+    use super::*;
+
+    fn graph() -> Graph {
+        let leaves = [
+            ("Keyword", "while", 10),
+            ("Ident", "[a-z]+", 2),
+            ("Number", "[0-9]+", 2),
+        ]
+        .into_iter()
+        .map(|(variant, src, priority)| {
+            let pattern = Pattern::compile(false, src, format!("{src:?}"), true, false)
+                .expect("Unable to compile pattern");
+            Leaf::new(Span::call_site(), pattern)
+                .variant_kind(VariantKind::Unit(variant.to_ident()))
+                .priority(priority)
+        })
+        .collect();
+        Graph::new(leaves, Config { utf8_mode: true }).expect("Unable to build graph")
+    }
+
+    #[test]
+    fn report() {
+        let graph = graph();
+        let tokens = quote! {
+            fn lex(lex: &mut Lexer) {
+                fn nested() {}
+            }
+        };
+
+        assert_snapshot!(Report::new("Token".into(), &graph, 2, &tokens), @r#"
+        Logos report for `Token`:
+          states:        8
+          leaves:        3
+          lookup tables: 2
+          functions:     2
+          token trees:   13
+          patterns with the most states:
+                7  #[regex("[a-z]+")] ::Ident
+                6  #[regex("while")] ::Keyword
+                2  #[regex("[0-9]+")] ::Number
+        "#);
+    }
+
+    #[test]
+    fn warning() {
+        let graph = graph();
+        let report = Report::new("Token".into(), &graph, 0, &TokenStream::new());
+        let warning = report.warning(Span::call_site()).to_string();
+
+        assert!(warning.contains("deprecated"));
+        assert!(warning.contains("Logos report for `Token`"));
+    }
+}
@@ -16,8 +16,18 @@ use syn::parse_file;
 #[case("explicit_lifetime1")]
 #[case("multiple_lifetime_failure")]
 #[case("explicit_lifetime_not_found")]
+#[case("codegen_attributes")]
+#[case("swar_loop")]
+#[case("keyword_chain")]
+#[case("padded")]
+#[case("profile_corpus")]
+#[case("const_fn")]
+#[case("max_len")]
+#[case("token_kind")]
 pub fn test_codegen(#[case] fixture: &str) -> Result<(), Box<dyn Error>> {
-    let codegen_alg = if cfg!(feature = "state_machine_codegen") {
+    let codegen_alg = if cfg!(feature = "table_codegen") {
+        "table"
+    } else if cfg!(feature = "state_machine_codegen") {
         "state_machine"
     } else {
         "tailcall"
@@ -0,0 +1,6 @@
+#[derive(Logos)]
+#[logos(codegen = "table", forbid_unsafe)]
+enum Token<'s> {
+    #[regex("[a-z]+")]
+    Word(&'s str),
+}
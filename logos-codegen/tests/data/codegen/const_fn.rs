@@ -0,0 +1,9 @@
+#[derive(Logos)]
+#[logos(const_fn, skip " +")]
+enum Token {
+    #[token("=")]
+    Equals,
+
+    #[regex("[0-9]+")]
+    Number,
+}
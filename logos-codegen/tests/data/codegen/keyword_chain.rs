@@ -0,0 +1,14 @@
+#[derive(Logos)]
+enum Token {
+    #[token("continue")]
+    Continue,
+
+    #[token("const")]
+    Const,
+
+    #[token("=>")]
+    Arrow,
+
+    #[regex("[0-9]+")]
+    Number,
+}
@@ -0,0 +1,9 @@
+#[derive(Logos)]
+#[logos(max_token_len = 64)]
+enum Token {
+    #[regex("[a-z]+")]
+    Word,
+
+    #[regex(r#""[^"]*""#, max_len = 1024)]
+    String,
+}
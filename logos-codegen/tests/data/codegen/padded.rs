@@ -0,0 +1,9 @@
+#[derive(Logos)]
+#[logos(padded)]
+enum Token {
+    #[token("=")]
+    Equals,
+
+    #[regex("[0-9]+")]
+    Number,
+}
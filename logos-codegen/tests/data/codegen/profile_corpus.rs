@@ -0,0 +1,19 @@
+#[derive(Logos)]
+#[logos(skip " ")]
+#[logos(profile_corpus = "tests/data/codegen/profile_corpus.csv")]
+enum Token {
+    #[token(",")]
+    Comma,
+
+    #[token("\n")]
+    Newline,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    #[regex("[a-zA-Z]+")]
+    Word,
+
+    #[regex(r#""[^"]*""#)]
+    Quoted,
+}
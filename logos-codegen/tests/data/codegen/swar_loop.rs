@@ -0,0 +1,5 @@
+#[derive(Logos)]
+enum Token {
+    #[regex(r#""([^"\\]|\\.)*""#)]
+    String,
+}
@@ -0,0 +1,9 @@
+#[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(kind)]
+enum Token<'s> {
+    #[regex("[a-z]+")]
+    Ident(&'s str),
+
+    #[token("+")]
+    Plus,
+}
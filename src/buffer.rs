@@ -0,0 +1,424 @@
+//! A buffer of lexed tokens, stored as a structure of arrays.
+//!
+//! See [`TokenBuffer`].
+
+use core::fmt::{self, Debug};
+use core::ops::Range;
+
+use crate::source::Source;
+use crate::{Lexer, Logos, Offset, Span, TokenKind};
+
+/// All tokens of a source, stored as a structure of arrays.
+///
+/// Each token is stored as a `u16` kind, see [`TokenKind`], along with its start and
+/// end offsets. Only tokens whose variant has fields, and errors, are also kept in
+/// full in a separate side table, so that parsers can index, search and slice the
+/// buffer without going through `(Result<Token, Error>, Span)` tuples. Offsets are
+/// stored as `usize` by default, or as `u32` with `TokenBuffer<Token, u32>`.
+///
+/// Built with [`Lexer::collect_buffer`], or [`TokenBuffer::from_lexer`] to pick
+/// the offset type.
+///
+/// # Example
+///
+/// ```
+/// use logos::{Logos, TokenBuffer, TokenKind};
+///
+/// #[derive(Logos, Debug, Clone, PartialEq)]
+/// #[logos(skip r"\s+")]
+/// #[logos(kind)]
+/// enum Token {
+///     #[regex("[a-z]+")]
+///     Ident,
+///
+///     #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+///     Number(u32),
+/// }
+///
+/// let buffer = Token::lexer("foo 42 bar").collect_buffer();
+///
+/// assert_eq!(buffer.len(), 3);
+/// assert_eq!(buffer.kinds(), &[Token::Ident.kind(), 1, Token::Ident.kind()]);
+/// assert_eq!(buffer.get(1), Some(Ok(Token::Number(42))));
+/// assert_eq!(buffer.span(2), Some(7..10));
+/// assert_eq!(buffer.slice(2), Some("bar"));
+///
+/// // Find the token at some offset in the source
+/// assert_eq!(buffer.find(5), Some(1));
+/// assert_eq!(buffer.find(3), None);
+///
+/// // Same with offsets stored as `u32`
+/// let buffer: TokenBuffer<Token, u32> = TokenBuffer::from_lexer(Token::lexer("foo 42 bar"));
+/// let tail = buffer.range(1..3);
+///
+/// assert_eq!(tail.len(), 2);
+/// assert_eq!(tail.span(0), Some(4..6));
+/// ```
+pub struct TokenBuffer<'source, Token: Logos<'source>, O: Offset = usize> {
+    source: &'source Token::Source,
+    kinds: Vec<u16>,
+    offsets: Vec<[O; 2]>,
+    /// Tokens with fields and errors, along with their index in the buffer
+    payloads: Vec<(O, Result<Token, Token::Error>)>,
+}
+
+/// A borrowed range of tokens of a [`TokenBuffer`].
+///
+/// Indices are relative to the start of the range, while spans are still
+/// offsets into the whole source.
+pub struct TokenSlice<'buffer, 'source, Token: Logos<'source>, O: Offset = usize> {
+    source: &'source Token::Source,
+    /// Index of the first token of the slice in the buffer
+    first: usize,
+    kinds: &'buffer [u16],
+    offsets: &'buffer [[O; 2]],
+    payloads: &'buffer [(O, Result<Token, Token::Error>)],
+}
+
+impl<'source, Token, O> TokenBuffer<'source, Token, O>
+where
+    Token: Logos<'source> + TokenKind,
+    O: Offset,
+{
+    /// Lex all remaining tokens of `lexer` into a new buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source is too long for offsets to fit in `O`.
+    pub fn from_lexer(mut lexer: Lexer<'source, Token>) -> Self {
+        // Checking once up front means `O::from_usize` can't fail in the loop. Every
+        // token is at least one byte long, so their indices fit in `O` as well.
+        O::from_usize(lexer.source.len());
+
+        let (lower, _) = lexer.size_hint();
+        let mut buffer = TokenBuffer {
+            source: lexer.source,
+            kinds: Vec::with_capacity(lower),
+            offsets: Vec::with_capacity(lower),
+            payloads: Vec::new(),
+        };
+
+        while let Some(token) = lexer.next() {
+            let kind = match &token {
+                Ok(token) => token.kind(),
+                Err(_) => Token::ERROR,
+            };
+
+            if unit::<Token>(kind).is_none() {
+                let index = O::from_usize(buffer.kinds.len());
+
+                buffer.payloads.push((index, token));
+            }
+            buffer.kinds.push(kind);
+            buffer.offsets.push([
+                O::from_usize(lexer.token_start),
+                O::from_usize(lexer.token_end),
+            ]);
+        }
+
+        buffer
+    }
+}
+
+impl<'source, Token, O> TokenBuffer<'source, Token, O>
+where
+    Token: Logos<'source>,
+    O: Offset,
+{
+    /// Borrow all tokens of the buffer.
+    #[inline]
+    pub fn as_slice(&self) -> TokenSlice<'_, 'source, Token, O> {
+        TokenSlice {
+            source: self.source,
+            first: 0,
+            kinds: &self.kinds,
+            offsets: &self.offsets,
+            payloads: &self.payloads,
+        }
+    }
+
+    /// Borrow the tokens within `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    #[inline]
+    pub fn range(&self, range: Range<usize>) -> TokenSlice<'_, 'source, Token, O> {
+        self.as_slice().range(range)
+    }
+
+    /// Source the tokens were lexed from.
+    #[inline]
+    pub fn source(&self) -> &'source Token::Source {
+        self.source
+    }
+
+    /// Number of tokens in the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.kinds.len()
+    }
+
+    /// Returns `true` if the buffer contains no tokens.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+    }
+
+    /// Get the kind of the token at `index`, see [`TokenKind`].
+    #[inline]
+    pub fn kind(&self, index: usize) -> Option<u16> {
+        self.kinds.get(index).copied()
+    }
+
+    /// Get the token at `index`.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<Result<Token, Token::Error>>
+    where
+        Token: TokenKind + Clone,
+    {
+        self.as_slice().get(index)
+    }
+
+    /// Get the span of the token at `index`.
+    #[inline]
+    pub fn span(&self, index: usize) -> Option<Span> {
+        self.as_slice().span(index)
+    }
+
+    /// Get the slice of the source matched by the token at `index`.
+    #[inline]
+    pub fn slice(&self, index: usize) -> Option<<Token::Source as Source>::Slice<'source>> {
+        self.as_slice().slice(index)
+    }
+
+    /// Find the index of the token whose span contains `offset`.
+    ///
+    /// Returns `None` if `offset` is in skipped input or past the last token.
+    #[inline]
+    pub fn find(&self, offset: usize) -> Option<usize> {
+        self.as_slice().find(offset)
+    }
+
+    /// Kinds of all tokens of the buffer, see [`TokenKind`].
+    #[inline]
+    pub fn kinds(&self) -> &[u16] {
+        &self.kinds
+    }
+
+    /// Start and end offsets of all tokens of the buffer.
+    #[inline]
+    pub fn offsets(&self) -> &[[O; 2]] {
+        &self.offsets
+    }
+
+    /// Iterate over the tokens of the buffer, along with their spans.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (Result<Token, Token::Error>, Span)> + '_
+    where
+        Token: TokenKind + Clone,
+    {
+        self.as_slice().iter()
+    }
+}
+
+impl<'buffer, 'source, Token, O> TokenSlice<'buffer, 'source, Token, O>
+where
+    Token: Logos<'source>,
+    O: Offset,
+{
+    /// Borrow the tokens within `range`, relative to this slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    #[inline]
+    pub fn range(&self, range: Range<usize>) -> TokenSlice<'buffer, 'source, Token, O> {
+        let payloads = self.payload_index(range.start)..self.payload_index(range.end);
+
+        TokenSlice {
+            source: self.source,
+            first: self.first + range.start,
+            kinds: &self.kinds[range.clone()],
+            offsets: &self.offsets[range],
+            payloads: &self.payloads[payloads],
+        }
+    }
+
+    /// Source the tokens were lexed from.
+    #[inline]
+    pub fn source(&self) -> &'source Token::Source {
+        self.source
+    }
+
+    /// Number of tokens in the slice.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.kinds.len()
+    }
+
+    /// Returns `true` if the slice contains no tokens.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+    }
+
+    /// Get the kind of the token at `index`, see [`TokenKind`].
+    #[inline]
+    pub fn kind(&self, index: usize) -> Option<u16> {
+        self.kinds.get(index).copied()
+    }
+
+    /// Get the token at `index`.
+    pub fn get(&self, index: usize) -> Option<Result<Token, Token::Error>>
+    where
+        Token: TokenKind + Clone,
+    {
+        let kind = *self.kinds.get(index)?;
+
+        match unit::<Token>(kind) {
+            Some(token) => Some(Ok(token)),
+            None => {
+                let (_, token) = &self.payloads[self.payload_index(index)];
+
+                Some(token.clone())
+            }
+        }
+    }
+
+    /// Get the span of the token at `index`.
+    #[inline]
+    pub fn span(&self, index: usize) -> Option<Span> {
+        let [start, end] = self.offsets.get(index)?;
+
+        Some(start.to_usize()..end.to_usize())
+    }
+
+    /// Get the slice of the source matched by the token at `index`.
+    #[inline]
+    pub fn slice(&self, index: usize) -> Option<<Token::Source as Source>::Slice<'source>> {
+        let [start, end] = self.offsets.get(index)?;
+
+        self.source.slice(start.to_usize()..end.to_usize())
+    }
+
+    /// Find the index of the token whose span contains `offset`.
+    ///
+    /// Returns `None` if `offset` is in skipped input or outside of the slice.
+    pub fn find(&self, offset: usize) -> Option<usize> {
+        let index = self
+            .offsets
+            .partition_point(|[start, _]| start.to_usize() <= offset)
+            .checked_sub(1)?;
+        let [_, end] = self.offsets[index];
+
+        match offset < end.to_usize() {
+            true => Some(index),
+            false => None,
+        }
+    }
+
+    /// Kinds of all tokens of the slice, see [`TokenKind`].
+    #[inline]
+    pub fn kinds(&self) -> &'buffer [u16] {
+        self.kinds
+    }
+
+    /// Start and end offsets of all tokens of the slice.
+    #[inline]
+    pub fn offsets(&self) -> &'buffer [[O; 2]] {
+        self.offsets
+    }
+
+    /// Iterate over the tokens of the slice, along with their spans.
+    pub fn iter(&self) -> impl Iterator<Item = (Result<Token, Token::Error>, Span)> + 'buffer
+    where
+        Token: TokenKind + Clone,
+    {
+        let mut payloads = self.payloads.iter();
+
+        self.kinds
+            .iter()
+            .zip(self.offsets)
+            .map(move |(&kind, [start, end])| {
+                let token = match unit::<Token>(kind) {
+                    Some(token) => Ok(token),
+                    None => payloads
+                        .next()
+                        .map(|(_, token)| token.clone())
+                        .expect("Token with fields missing from the side table"),
+                };
+
+                (token, start.to_usize()..end.to_usize())
+            })
+    }
+
+    /// Index in the side table of the first token with fields at or after `index`.
+    fn payload_index(&self, index: usize) -> usize {
+        let index = self.first + index;
+
+        self.payloads
+            .partition_point(|(payload, _)| payload.to_usize() < index)
+    }
+}
+
+/// Token of a variant without fields, which isn't stored in the side table.
+#[inline]
+fn unit<Token: TokenKind>(kind: u16) -> Option<Token> {
+    match kind == Token::ERROR {
+        true => None,
+        false => Token::from_kind(kind),
+    }
+}
+
+impl<'source, Token, O> Clone for TokenBuffer<'source, Token, O>
+where
+    Token: Logos<'source> + Clone,
+    O: Offset,
+{
+    fn clone(&self) -> Self {
+        TokenBuffer {
+            source: self.source,
+            kinds: self.kinds.clone(),
+            offsets: self.offsets.clone(),
+            payloads: self.payloads.clone(),
+        }
+    }
+}
+
+impl<'source, Token, O> Clone for TokenSlice<'_, 'source, Token, O>
+where
+    Token: Logos<'source>,
+    O: Offset,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'source, Token, O> Copy for TokenSlice<'_, 'source, Token, O>
+where
+    Token: Logos<'source>,
+    O: Offset,
+{
+}
+
+impl<'source, Token, O> Debug for TokenBuffer<'source, Token, O>
+where
+    Token: Logos<'source> + TokenKind + Clone + Debug,
+    O: Offset,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl<'source, Token, O> Debug for TokenSlice<'_, 'source, Token, O>
+where
+    Token: Logos<'source> + TokenKind + Clone + Debug,
+    O: Offset,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,245 @@
+//! Stock callbacks for constructs that can't be expressed with a regular expression.
+//!
+//! Functions in this module return closures, so they are used by calling them
+//! in place of a callback. Since `name(...)` is read as a named argument inside
+//! `#[token]` and `#[regex]`, either spell out the path or use `callback = name(...)`:
+//!
+//! ```rust
+//! use logos::Logos;
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! #[logos(skip r"\s+")]
+//! #[logos(skip(r"/\*", logos::callbacks::nested("/*", "*/")))]
+//! #[logos(error = logos::callbacks::Unterminated)]
+//! enum Token {
+//!     #[regex("[a-z]+")]
+//!     Word,
+//! }
+//!
+//! let mut lex = Token::lexer("foo /* bar /* baz */ */ qux");
+//!
+//! assert_eq!(lex.next(), Some(Ok(Token::Word)));
+//! assert_eq!(lex.next(), Some(Ok(Token::Word)));
+//! assert_eq!(lex.slice(), "qux");
+//! assert_eq!(lex.next(), None);
+//! ```
+
+use crate::lexer::Needle;
+use crate::source::Source;
+use crate::{Lexer, Logos, Span};
+
+/// Error returned by stock callbacks when the input ends before a closing
+/// delimiter was found.
+///
+/// Implement `From<Unterminated>` for your error type to use those callbacks
+/// with `#[logos(error = ...)]`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Unterminated {
+    /// Span of the opening delimiter.
+    pub span: Span,
+}
+
+/// Create a callback consuming balanced, nested block delimited by `open` and `close`,
+/// such as `/* /* */ */` comments.
+///
+/// The callback should be attached to a pattern matching `open`. It bumps the lexer
+/// past the `close` delimiter balancing the initial match. If the input ends before that,
+/// the rest of the input is consumed and an [`Unterminated`] error carrying the span of
+/// the initial match is returned.
+///
+/// # Panics
+///
+/// Panics if `open` or `close` is empty, since an empty delimiter matches everywhere
+/// and the block could never be balanced.
+///
+/// # Example
+///
+/// ```rust
+/// use logos::callbacks::{nested, Unterminated};
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(skip r"\s+")]
+/// #[logos(error = Unterminated)]
+/// enum Token {
+///     #[token("(*", callback = nested("(*", "*)"))]
+///     Comment,
+///
+///     #[regex("[a-z]+")]
+///     Word,
+/// }
+///
+/// let mut lex = Token::lexer("(* (* *) *) foo (* (* bar *)");
+///
+/// assert_eq!(lex.next(), Some(Ok(Token::Comment)));
+/// assert_eq!(lex.slice(), "(* (* *) *)");
+/// assert_eq!(lex.next(), Some(Ok(Token::Word)));
+/// assert_eq!(lex.next(), Some(Err(Unterminated { span: 16..18 })));
+/// assert_eq!(lex.slice(), "(* (* bar *)");
+/// assert_eq!(lex.next(), None);
+/// ```
+pub fn nested<'source, Token, O, C>(
+    open: O,
+    close: C,
+) -> impl Fn(&mut Lexer<'source, Token>) -> Result<(), Unterminated>
+where
+    Token: Logos<'source>,
+    O: Needle,
+    C: Needle,
+{
+    assert!(
+        !open.as_bytes().is_empty() && !close.as_bytes().is_empty(),
+        "nested delimiters must not be empty",
+    );
+
+    move |lex| {
+        let open = open.as_bytes();
+        let close = close.as_bytes();
+        let start = lex.span();
+
+        let mut offset = start.end;
+        let mut next_open = lex.find_needle(offset, open);
+        let mut depth = 1usize;
+
+        while let Some(next_close) = lex.find_needle(offset, close) {
+            match next_open {
+                Some(at) if at < next_close => {
+                    depth += 1;
+                    offset = at + open.len();
+                    next_open = lex.find_needle(offset, open);
+                }
+                _ => {
+                    depth -= 1;
+                    offset = next_close + close.len();
+
+                    if depth == 0 {
+                        lex.bump(offset - start.end);
+                        return Ok(());
+                    }
+                    if next_open.is_some_and(|at| at < offset) {
+                        next_open = lex.find_needle(offset, open);
+                    }
+                }
+            }
+        }
+
+        lex.bump(lex.source().len() - start.end);
+
+        Err(Unterminated { span: start })
+    }
+}
+
+/// Content and fence of a literal matched by [`fenced`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fenced<S> {
+    /// Everything between the opening and the closing delimiter.
+    pub content: S,
+    /// The variable part of the delimiters, such as `##` in `r##"foo"##`.
+    pub fence: S,
+}
+
+/// Create a callback consuming a literal whose closing delimiter depends on the
+/// opening one, such as Rust raw strings or shell heredocs.
+///
+/// The callback should be attached to a pattern matching the opening delimiter,
+/// which is made of `prefix`, followed by a fence of any length, followed by `suffix`.
+/// The callback then searches for the closing delimiter, made of `close` followed by
+/// the same fence, and emits a [`Fenced`] with the content and fence of the literal.
+/// When `close` ends with a newline, as it does for heredocs, the closing delimiter
+/// must span a whole line: the fence has to be followed by a line break or the end of
+/// the input. If the input ends before that, the rest of the input is consumed and an
+/// [`Unterminated`] error carrying the span of the opening delimiter is returned.
+///
+/// # Example
+///
+/// ```rust
+/// use logos::callbacks::{fenced, Fenced, Unterminated};
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(skip r"\s+")]
+/// #[logos(error = Unterminated)]
+/// enum Token<'s> {
+///     // `r##"foo"##`
+///     #[regex(r##"r#*""##, callback = fenced("r", "\"", "\""))]
+///     RawString(Fenced<&'s str>),
+///
+///     // `<<EOF\nfoo\nEOF`
+///     #[regex(r"<<[A-Z]+\n", callback = fenced("<<", "\n", "\n"))]
+///     Heredoc(Fenced<&'s str>),
+/// }
+///
+/// let mut lex = Token::lexer("r#\"\"foo\"\"# <<END\nbar\nEND\nr##\"baz\"#");
+///
+/// assert_eq!(
+///     lex.next(),
+///     Some(Ok(Token::RawString(Fenced { content: "\"foo\"", fence: "#" }))),
+/// );
+/// assert_eq!(
+///     lex.next(),
+///     Some(Ok(Token::Heredoc(Fenced { content: "bar", fence: "END" }))),
+/// );
+/// assert_eq!(lex.next(), Some(Err(Unterminated { span: 25..29 })));
+/// assert_eq!(lex.next(), None);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn fenced<'source, Token, P, S, C>(
+    prefix: P,
+    suffix: S,
+    close: C,
+) -> impl Fn(
+    &mut Lexer<'source, Token>,
+) -> Result<Fenced<<Token::Source as Source>::Slice<'source>>, Unterminated>
+where
+    Token: Logos<'source>,
+    P: Needle,
+    S: Needle,
+    C: Needle,
+{
+    move |lex| {
+        let close = close.as_bytes();
+        let whole_line = close.last() == Some(&b'\n');
+        let start = lex.span();
+        let fence = start.start + prefix.as_bytes().len()..start.end - suffix.as_bytes().len();
+        let source = lex.source();
+
+        let mut offset = start.end;
+
+        while let Some(at) = lex.find_needle(offset, close) {
+            offset = at + 1;
+
+            let fence_at = at + close.len();
+            let end = fence_at + fence.len();
+            let matches = fence
+                .clone()
+                .enumerate()
+                .all(|(i, f)| lex.read_source::<u8>(fence_at + i) == lex.read_source::<u8>(f));
+
+            if !matches || (whole_line && !ends_line(lex, end)) {
+                continue;
+            }
+
+            // Needles that aren't whole characters can match in the middle of one,
+            // in which case the literal can't be sliced there.
+            if let (Some(content), Some(fence)) =
+                (source.slice(start.end..at), source.slice(fence.clone()))
+            {
+                lex.bump(end - start.end);
+
+                return Ok(Fenced { content, fence });
+            }
+        }
+
+        lex.bump(source.len() - start.end);
+
+        Err(Unterminated { span: start })
+    }
+}
+
+fn ends_line<'source, Token: Logos<'source>>(lex: &Lexer<'source, Token>, offset: usize) -> bool {
+    match lex.read_source::<u8>(offset) {
+        None | Some(b'\n') => true,
+        Some(b'\r') => matches!(lex.read_source::<u8>(offset + 1), None | Some(b'\n')),
+        _ => false,
+    }
+}
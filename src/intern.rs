@@ -0,0 +1,64 @@
+//! Interning of token slices, see [`Interner`].
+
+/// Symbol table that token slices can be interned into, with the `intern` option of
+/// `#[token]` and `#[regex]`, or with [`Lexer::intern`](crate::Lexer::intern) in callbacks.
+///
+/// This is implemented by the `Extras` of the lexer. `Slice` is the type of
+/// slices of the source, `&'source str` or `&'source [u8]`, so interners are
+/// free to keep them around without copying.
+///
+/// # Example
+///
+/// ```
+/// use logos::{Interner, Logos};
+/// use std::collections::HashMap;
+///
+/// #[derive(Default)]
+/// struct Symbols<'source> {
+///     ids: HashMap<&'source str, u32>,
+/// }
+///
+/// impl<'source> Interner<&'source str> for Symbols<'source> {
+///     type Symbol = u32;
+///
+///     fn intern(&mut self, slice: &'source str) -> u32 {
+///         let next = self.ids.len() as u32;
+///
+///         *self.ids.entry(slice).or_insert(next)
+///     }
+/// }
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(skip " +")]
+/// #[logos(extras = Symbols<'s>)]
+/// enum Token {
+///     #[regex("[a-z_]+", intern)]
+///     Ident(u32),
+/// }
+///
+/// let mut lex = Token::lexer("foo bar foo");
+///
+/// assert_eq!(lex.next(), Some(Ok(Token::Ident(0))));
+/// assert_eq!(lex.next(), Some(Ok(Token::Ident(1))));
+/// assert_eq!(lex.next(), Some(Ok(Token::Ident(0))));
+/// assert_eq!(lex.extras.ids.len(), 2);
+/// ```
+pub trait Interner<Slice> {
+    /// Compact identifier of an interned slice.
+    type Symbol;
+
+    /// Get the symbol of `slice`, adding it to the table if needed.
+    fn intern(&mut self, slice: Slice) -> Self::Symbol;
+}
+
+impl<Slice, I> Interner<Slice> for &mut I
+where
+    I: Interner<Slice> + ?Sized,
+{
+    type Symbol = I::Symbol;
+
+    #[inline]
+    fn intern(&mut self, slice: Slice) -> Self::Symbol {
+        (**self).intern(slice)
+    }
+}
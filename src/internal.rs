@@ -1,6 +1,10 @@
 use crate::source::Chunk;
 use crate::{Filter, FilterResult, Logos, Skip};
 
+/// Owned types of the fields of tokens generated by `#[logos(owned = ...)]`.
+#[cfg(feature = "alloc")]
+pub use alloc::{string::String, vec::Vec};
+
 /// Trait used by the functions contained in the `Lexicon`.
 ///
 /// # WARNING!
@@ -16,6 +20,9 @@ pub trait LexerInternal<'source> {
     /// Read a chunk
     fn read<T: Chunk<'source>>(&self, offset: usize) -> Option<T>;
 
+    /// Read a chunk without using unsafe code
+    fn read_safe<T: Chunk<'source>>(&self, offset: usize) -> Option<T>;
+
     /// Reset `token_start` to `token_end`.
     fn trivia(&mut self);
 
@@ -26,10 +33,39 @@ pub trait LexerInternal<'source> {
     /// Set `token_end` to an offset.
     fn end(&mut self, offset: usize);
 
+    /// Set the index of the pattern that matched the current token.
+    fn set_rule(&mut self, index: Option<u32>);
+
     /// Returns if the source is only a prefix of the full input.
     fn is_prefix(&self) -> bool;
 }
 
+/// Reads the byte at `offset` of a lexer deriving `#[logos(padded)]`.
+///
+/// Without the `forbid_unsafe` feature this skips bounds checks with
+/// [`Padded::read_byte_unchecked`](crate::source::Padded::read_byte_unchecked), which is
+/// sound as generated code only reads one byte past the bytes it already read, and padded
+/// sources return `None` at their end. With the feature it is a checked read, so generated
+/// code doesn't depend on the features `logos-codegen` was built with.
+#[cfg(not(feature = "forbid_unsafe"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __read_byte {
+    ($lex:ident, $offset:expr) => {
+        unsafe { $lex.source().read_byte_unchecked($offset) }
+    };
+}
+
+/// Reads the byte at `offset` of a lexer deriving `#[logos(padded)]`, with bounds checks.
+#[cfg(feature = "forbid_unsafe")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __read_byte {
+    ($lex:ident, $offset:expr) => {
+        $lex.read::<::core::primitive::u8>($offset)
+    };
+}
+
 //TODO: Seems to me that we are missing a way to return Ok(Token::Uint) or skip matched input,
 // similar to Filter<T> but for unit variants.
 pub enum CallbackResult<'a, L: Logos<'a>> {
@@ -251,3 +287,82 @@ impl<'a, L: Logos<'a>, E: Into<L::Error>> SkipRetVal<'a, L> for Result<Skip, E>
         }
     }
 }
+
+/// Storage of the warnings recorded by a [`Lexer`](crate::Lexer), `()` for tokens
+/// without a warning type.
+pub trait WarningStore: Default + Clone + 'static {
+    /// Number of warnings recorded so far, including those already taken.
+    fn recorded(&self) -> usize;
+
+    /// Discard the warnings recorded after the first `recorded` ones.
+    fn restore(&mut self, recorded: usize);
+
+    /// Add the warnings of `other` to this store if they have the same type,
+    /// otherwise drop them.
+    fn absorb<S: WarningStore>(&mut self, other: S);
+}
+
+impl WarningStore for () {
+    #[inline]
+    fn recorded(&self) -> usize {
+        0
+    }
+
+    #[inline]
+    fn restore(&mut self, _: usize) {}
+
+    #[inline]
+    fn absorb<S: WarningStore>(&mut self, _: S) {}
+}
+
+/// Warnings of type `W` recorded by a [`Lexer`](crate::Lexer).
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct WarningList<W> {
+    pub(crate) warnings: Vec<(W, crate::Span)>,
+
+    /// Number of warnings taken so far, so that restoring a checkpoint made
+    /// before they were taken only discards the warnings recorded since.
+    pub(crate) taken: usize,
+}
+
+#[cfg(feature = "std")]
+impl<W> Default for WarningList<W> {
+    fn default() -> Self {
+        WarningList {
+            warnings: Vec::new(),
+            taken: 0,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W> WarningList<W> {
+    pub(crate) fn take(&mut self) -> Vec<(W, crate::Span)> {
+        self.taken += self.warnings.len();
+
+        core::mem::take(&mut self.warnings)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: Clone + 'static> WarningStore for WarningList<W> {
+    #[inline]
+    fn recorded(&self) -> usize {
+        self.taken + self.warnings.len()
+    }
+
+    #[inline]
+    fn restore(&mut self, recorded: usize) {
+        self.warnings.truncate(recorded.saturating_sub(self.taken));
+    }
+
+    fn absorb<S: WarningStore>(&mut self, other: S) {
+        let mut other = Some(other);
+        let other: &mut dyn core::any::Any = &mut other;
+
+        if let Some(other) = other.downcast_mut::<Option<Self>>().and_then(Option::take) {
+            self.warnings.extend(other.warnings);
+        }
+    }
+}
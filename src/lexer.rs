@@ -1,23 +1,77 @@
-use super::internal::LexerInternal;
+use super::internal::{LexerInternal, WarningStore};
 use super::Logos;
 use crate::source::{self, Source};
+use crate::Interner;
+#[cfg(feature = "std")]
+use crate::{TokenBuffer, TokenKind, Warnings};
 
 use core::fmt::{self, Debug};
-use core::ops::{Deref, DerefMut};
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut, Range};
 
 /// Byte range in the source.
 pub type Span = core::ops::Range<usize>;
 
+/// Integer type used to store offsets into the source, in the spans produced by
+/// [`Lexer::spanned_as`] and in a [`TokenBuffer`](crate::TokenBuffer).
+///
+/// Implemented for `usize`, and for `u32` to halve the size of the spans
+/// of sources shorter than 4 GiB.
+pub trait Offset: Copy + Ord + Debug + sealed::Sealed {
+    /// Convert an offset into the source, panicking if it doesn't fit.
+    fn from_usize(offset: usize) -> Self;
+
+    /// Convert back into an offset into the source.
+    fn to_usize(self) -> usize;
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for usize {}
+    impl Sealed for u32 {}
+}
+
+impl Offset for usize {
+    #[inline]
+    fn from_usize(offset: usize) -> Self {
+        offset
+    }
+
+    #[inline]
+    fn to_usize(self) -> usize {
+        self
+    }
+}
+
+impl Offset for u32 {
+    #[inline]
+    fn from_usize(offset: usize) -> Self {
+        u32::try_from(offset).expect("source offset doesn't fit in the offset type")
+    }
+
+    #[inline]
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+}
+
 /// `Lexer` is the main struct of the crate that allows you to read through a
 /// `Source` and produce tokens for enums implementing the `Logos` trait.
 pub struct Lexer<'source, Token: Logos<'source>> {
-    source: &'source Token::Source,
+    pub(crate) source: &'source Token::Source,
 
     /// True if `source` is not the full input but a prefix of it
-    is_prefix: bool,
+    pub(crate) is_prefix: bool,
 
-    token_start: usize,
-    token_end: usize,
+    pub(crate) token_start: usize,
+    pub(crate) token_end: usize,
+
+    /// Index of the pattern that matched the current token, see [`Lexer::rule`].
+    pub(crate) rule: Option<u32>,
+
+    /// Warnings recorded by callbacks, see [`Lexer::warn`].
+    pub(crate) warnings: Token::WarningStore,
 
     /// Extras associated with the `Token`.
     pub extras: Token::Extras,
@@ -60,6 +114,8 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
             extras,
             token_start: 0,
             token_end: 0,
+            rule: None,
+            warnings: Default::default(),
         }
     }
 
@@ -83,6 +139,8 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
             extras,
             token_start: 0,
             token_end: 0,
+            rule: None,
+            warnings: Default::default(),
         }
     }
 
@@ -146,7 +204,77 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
     /// ```
     #[inline]
     pub fn spanned(self) -> SpannedIter<'source, Token> {
-        SpannedIter { lexer: self }
+        SpannedIter {
+            lexer: self,
+            offset: PhantomData,
+        }
+    }
+
+    /// Like [`Lexer::spanned`], but with the offsets of spans stored as `O`, such as
+    /// `u32` to halve the size of spans.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source is too long for its offsets to fit in `O`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use logos::Logos;
+    /// use std::ops::Range;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(skip " +")]
+    /// enum Token {
+    ///     #[regex("[a-z]+")]
+    ///     Word,
+    /// }
+    ///
+    /// let spans: Vec<Range<u32>> = Token::lexer("foo bar")
+    ///     .spanned_as::<u32>()
+    ///     .map(|(_, span)| span)
+    ///     .collect();
+    ///
+    /// assert_eq!(spans, [0..3, 4..7]);
+    /// ```
+    pub fn spanned_as<O: Offset>(self) -> SpannedIter<'source, Token, O> {
+        // Checking once up front means `O::from_usize` can't fail while iterating
+        O::from_usize(self.source.len());
+
+        SpannedIter {
+            lexer: self,
+            offset: PhantomData,
+        }
+    }
+
+    /// Lex all remaining tokens into a [`TokenBuffer`], storing the kinds of tokens,
+    /// their payloads and their offsets in separate arrays.
+    ///
+    /// Use [`TokenBuffer::from_lexer`] to store offsets as `u32` instead of `usize`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, Clone, PartialEq)]
+    /// #[logos(skip " +", kind)]
+    /// enum Token {
+    ///     #[regex("[a-z]+")]
+    ///     Word,
+    /// }
+    ///
+    /// let buffer = Token::lexer("foo bar").collect_buffer();
+    ///
+    /// assert_eq!(buffer.iter().map(|(token, _)| token).collect::<Vec<_>>(), [Ok(Token::Word), Ok(Token::Word)]);
+    /// assert_eq!(buffer.offsets(), &[[0, 3], [4, 7]]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn collect_buffer(self) -> TokenBuffer<'source, Token>
+    where
+        Token: TokenKind,
+    {
+        TokenBuffer::from_lexer(self)
     }
 
     #[inline]
@@ -162,6 +290,49 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
         self.token_start..self.token_end
     }
 
+    /// Get the pattern that matched the current token.
+    ///
+    /// This lets a callback shared by several `#[token]` and `#[regex]` attributes
+    /// tell which one of them fired. Returns `None` before the first token, and when
+    /// the current token is an error produced because no pattern matched, or because
+    /// the token is over its length limit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use logos::{Lexer, Logos};
+    ///
+    /// fn integer(lex: &mut Lexer<Token>) -> Option<u64> {
+    ///     let (radix, prefix) = match lex.rule()?.pattern() {
+    ///         "0x[0-9a-f]+" => (16, 2),
+    ///         "0b[01]+" => (2, 2),
+    ///         _ => (10, 0),
+    ///     };
+    ///
+    ///     u64::from_str_radix(&lex.slice()[prefix..], radix).ok()
+    /// }
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(skip " +")]
+    /// enum Token {
+    ///     #[regex("[0-9]+", integer)]
+    ///     #[regex("0x[0-9a-f]+", integer)]
+    ///     #[regex("0b[01]+", integer)]
+    ///     Integer(u64),
+    /// }
+    ///
+    /// let mut lex = Token::lexer("10 0x10 0b10");
+    ///
+    /// assert_eq!(lex.next(), Some(Ok(Token::Integer(10))));
+    /// assert_eq!(lex.rule().map(|rule| rule.index()), Some(1));
+    /// assert_eq!(lex.next(), Some(Ok(Token::Integer(16))));
+    /// assert_eq!(lex.next(), Some(Ok(Token::Integer(2))));
+    /// ```
+    #[inline]
+    pub fn rule(&self) -> Option<RuleId> {
+        self.rule.map(RuleId::of::<Token>)
+    }
+
     /// Get a string slice of the current token.
     #[inline]
     pub fn slice(&self) -> <Token::Source as Source>::Slice<'source> {
@@ -173,27 +344,47 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
         // * Thus safety is contingent on the correct implementation of the `is_boundary`
         //   method.
         #[cfg(not(feature = "forbid_unsafe"))]
-        unsafe {
-            self.source.slice_unchecked(self.span())
+        if !Token::FORBID_UNSAFE {
+            return unsafe {
+                self.source
+                    .slice_unchecked(self.token_start..self.token_end)
+            };
         }
-        #[cfg(feature = "forbid_unsafe")]
-        self.source.slice(self.span()).unwrap()
+
+        self.source.slice(self.token_start..self.token_end).unwrap()
     }
 
     /// Get a slice of remaining source, starting at the end of current token.
     #[inline]
     pub fn remainder(&self) -> <Token::Source as Source>::Slice<'source> {
         #[cfg(not(feature = "forbid_unsafe"))]
-        unsafe {
-            self.source
-                .slice_unchecked(self.token_end..self.source.len())
+        if !Token::FORBID_UNSAFE {
+            return unsafe {
+                self.source
+                    .slice_unchecked(self.token_end..self.source.len())
+            };
         }
-        #[cfg(feature = "forbid_unsafe")]
+
         self.source
             .slice(self.token_end..self.source.len())
             .unwrap()
     }
 
+    /// Intern the slice of the current token into `Extras`, returning its symbol.
+    ///
+    /// This is what the `intern` option of `#[token]` and `#[regex]` calls, see [`Interner`].
+    #[inline]
+    pub fn intern(
+        &mut self,
+    ) -> <Token::Extras as Interner<<Token::Source as Source>::Slice<'source>>>::Symbol
+    where
+        Token::Extras: Interner<<Token::Source as Source>::Slice<'source>>,
+    {
+        let slice = self.slice();
+
+        self.extras.intern(slice)
+    }
+
     /// Turn this lexer into a lexer for a new token type.
     ///
     /// The new lexer continues to point at the same span as the current lexer,
@@ -209,9 +400,250 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
             extras: self.extras.into(),
             token_start: self.token_start,
             token_end: self.token_end,
+            rule: None,
+            warnings: {
+                let mut warnings = Token2::WarningStore::default();
+                warnings.absorb(self.warnings);
+                warnings
+            },
+        }
+    }
+
+    /// Record a non-fatal diagnostic for the current token.
+    ///
+    /// Unlike returning an error from a callback, this doesn't prevent the token from
+    /// being emitted. Recorded warnings are kept, along with the span of the current
+    /// token, until taken with [`Lexer::take_warnings`]. They are carried over by
+    /// [`Lexer::morph`] and [`Lexer::sublex`] to lexers whose token has the same
+    /// warning type, and dropped otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use logos::Logos;
+    ///
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// enum Warning {
+    ///     LeadingZero,
+    /// }
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(skip r"\s+")]
+    /// #[logos(warning = Warning)]
+    /// enum Token {
+    ///     #[regex("[0-9]+", |lex| {
+    ///         if lex.slice().len() > 1 && lex.slice().starts_with('0') {
+    ///             lex.warn(Warning::LeadingZero);
+    ///         }
+    ///         lex.slice().parse().ok()
+    ///     })]
+    ///     Number(u64),
+    /// }
+    ///
+    /// let mut lex = Token::lexer("10 010");
+    ///
+    /// assert_eq!(lex.next(), Some(Ok(Token::Number(10))));
+    /// assert_eq!(lex.next(), Some(Ok(Token::Number(10))));
+    /// assert_eq!(lex.take_warnings(), vec![(Warning::LeadingZero, 3..6)]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn warn(&mut self, warning: Token::Warning)
+    where
+        Token: Warnings<'source>,
+    {
+        let span = self.span();
+
+        Token::warning_list_mut(&mut self.warnings)
+            .warnings
+            .push((warning, span));
+    }
+
+    /// Warnings recorded with [`Lexer::warn`] that haven't been taken yet.
+    #[cfg(feature = "std")]
+    pub fn warnings(&self) -> impl Iterator<Item = (&Token::Warning, Span)>
+    where
+        Token: Warnings<'source>,
+    {
+        Token::warning_list(&self.warnings)
+            .warnings
+            .iter()
+            .map(|(warning, span)| (warning, span.clone()))
+    }
+
+    /// Take all warnings recorded with [`Lexer::warn`] so far.
+    #[cfg(feature = "std")]
+    pub fn take_warnings(&mut self) -> Vec<(Token::Warning, Span)>
+    where
+        Token: Warnings<'source>,
+    {
+        Token::warning_list_mut(&mut self.warnings).take()
+    }
+
+    /// Run a lexer for another token type on the input following the current token,
+    /// then extend the current token up to where that lexer stopped.
+    ///
+    /// This is meant to be used inside callbacks, for instance to collect the tokens of
+    /// an embedded language into the payload of the outer token. Spans produced by the
+    /// inner lexer are offsets into the same `Source`, and warnings recorded by the inner
+    /// lexer are added to the warnings of this one if they have the same type.
+    ///
+    /// The `Extras` of the inner lexer are built by `extras` from a mutable borrow of the
+    /// `Extras` of this one, which lasts as long as the inner lexer. Inner token types
+    /// sharing the state of the outer one declare borrowed extras, such as
+    /// `#[logos(extras = &'e mut State)]`, and others can ignore it with `|_| ()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use logos::{Lexer, Logos};
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(skip r"\s+")]
+    /// enum Outer {
+    ///     #[token("[", |lex| lex.sublex(|_| (), list))]
+    ///     List(Vec<u32>),
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Word,
+    /// }
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(skip r"[\s,]+")]
+    /// enum Inner {
+    ///     #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    ///     Number(u32),
+    ///
+    ///     #[token("]")]
+    ///     End,
+    /// }
+    ///
+    /// fn list(lex: &mut Lexer<Inner>) -> Option<Vec<u32>> {
+    ///     let mut numbers = Vec::new();
+    ///
+    ///     while let Some(token) = lex.next() {
+    ///         match token.ok()? {
+    ///             Inner::Number(n) => numbers.push(n),
+    ///             Inner::End => return Some(numbers),
+    ///         }
+    ///     }
+    ///     None
+    /// }
+    ///
+    /// let mut lex = Outer::lexer("foo [1, 2, 3] bar");
+    ///
+    /// assert_eq!(lex.next(), Some(Ok(Outer::Word)));
+    /// assert_eq!(lex.next(), Some(Ok(Outer::List(vec![1, 2, 3]))));
+    /// assert_eq!(lex.span(), 4..13);
+    /// assert_eq!(lex.next(), Some(Ok(Outer::Word)));
+    /// assert_eq!(lex.next(), None);
+    /// ```
+    pub fn sublex<'a, Inner, E, F, R>(&'a mut self, extras: E, f: F) -> R
+    where
+        Inner: Logos<'a, Source = Token::Source>,
+        E: FnOnce(&'a mut Token::Extras) -> Inner::Extras,
+        F: FnOnce(&mut Lexer<'a, Inner>) -> R,
+    {
+        let mut inner = Lexer {
+            source: self.source,
+            is_prefix: self.is_prefix,
+            extras: extras(&mut self.extras),
+            token_start: self.token_end,
+            token_end: self.token_end,
+            rule: None,
+            warnings: Default::default(),
+        };
+
+        let result = f(&mut inner);
+
+        self.token_end = inner.token_end;
+
+        self.warnings.absorb(inner.warnings);
+
+        result
+    }
+
+    /// Save the position of the lexer and a snapshot of its `Extras`, to go back to
+    /// it later with [`Lexer::restore`].
+    ///
+    /// Unlike cloning the lexer, this doesn't require `Token` or `Extras` to be `Clone`,
+    /// only `Extras` to implement [`SnapshotExtras`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(skip " +")]
+    /// #[logos(extras = usize)]
+    /// enum Token {
+    ///     #[regex("[a-z]+", |lex| lex.extras += 1)]
+    ///     Word,
+    ///
+    ///     #[token("!")]
+    ///     Bang,
+    /// }
+    ///
+    /// let mut lex = Token::lexer("foo bar baz !");
+    ///
+    /// assert_eq!(lex.next(), Some(Ok(Token::Word)));
+    ///
+    /// let checkpoint = lex.checkpoint();
+    ///
+    /// assert_eq!(lex.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lex.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lex.extras, 3);
+    ///
+    /// // Backtrack to right after "foo"
+    /// lex.restore(checkpoint);
+    ///
+    /// assert_eq!(lex.span(), 0..3);
+    /// assert_eq!(lex.extras, 1);
+    /// assert_eq!(lex.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lex.slice(), "bar");
+    /// ```
+    pub fn checkpoint(&self) -> Checkpoint<<Token::Extras as SnapshotExtras>::Snapshot>
+    where
+        Token::Extras: SnapshotExtras,
+    {
+        Checkpoint {
+            token_start: self.token_start,
+            token_end: self.token_end,
+            rule: self.rule,
+            extras: self.extras.snapshot(),
+            warnings: self.warnings.recorded(),
         }
     }
 
+    /// Go back to a position saved with [`Lexer::checkpoint`], restoring `Extras`
+    /// from their snapshot.
+    ///
+    /// Warnings recorded since the checkpoint are discarded, unless they were taken
+    /// in the meantime. The checkpoint must come
+    /// from this lexer, or from a lexer reading the same source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checkpoint is out of bounds of the source, or in the middle of
+    /// an UTF-8 code point (does not apply when lexing raw `&[u8]`).
+    pub fn restore(&mut self, checkpoint: Checkpoint<<Token::Extras as SnapshotExtras>::Snapshot>)
+    where
+        Token::Extras: SnapshotExtras,
+    {
+        assert!(
+            self.source.is_boundary(checkpoint.token_start)
+                && self.source.is_boundary(checkpoint.token_end),
+            "Invalid Lexer checkpoint",
+        );
+
+        self.token_start = checkpoint.token_start;
+        self.token_end = checkpoint.token_end;
+        self.rule = checkpoint.rule;
+        self.extras.restore(checkpoint.extras);
+
+        self.warnings.restore(checkpoint.warnings);
+    }
+
     /// Bumps the end of currently lexed token by `n` bytes.
     ///
     /// # Panics
@@ -226,8 +658,429 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
             "Invalid Lexer bump",
         )
     }
+
+    /// Moves the end of currently lexed token to `offset` in the source, giving the
+    /// bytes past it back to the remainder, or consuming more input like [`Lexer::bump`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is not past the start of the current token, is beyond the last
+    /// byte, or is in the middle of an UTF-8 code point (does not apply when lexing
+    /// raw `&[u8]`).
+    pub fn set_end(&mut self, offset: usize) {
+        assert!(
+            offset > self.token_start && self.source.is_boundary(offset),
+            "Invalid Lexer end",
+        );
+
+        self.token_end = offset;
+    }
+
+    /// Moves the end of currently lexed token back by `n` bytes, giving them back
+    /// to the remainder. This is the opposite of [`Lexer::bump`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not smaller than the length of the current token, or if it would
+    /// place the end in the middle of an UTF-8 code point (does not apply when lexing
+    /// raw `&[u8]`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use logos::{Lexer, Logos};
+    ///
+    /// fn number(lex: &mut Lexer<Token>) -> Option<f64> {
+    ///     // `1.` followed by a method call or a range is an integer
+    ///     let slice = lex.slice();
+    ///     if slice.ends_with('.') && lex.remainder().starts_with(|c: char| c == '.' || c.is_alphabetic()) {
+    ///         lex.unbump(1);
+    ///     }
+    ///     lex.slice().parse().ok()
+    /// }
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[0-9]+(\.[0-9]*)?", number)]
+    ///     Number(f64),
+    ///
+    ///     #[token("..")]
+    ///     Range,
+    /// }
+    ///
+    /// let mut lex = Token::lexer("1..2.");
+    ///
+    /// assert_eq!(lex.next(), Some(Ok(Token::Number(1.0))));
+    /// assert_eq!(lex.span(), 0..1);
+    /// assert_eq!(lex.next(), Some(Ok(Token::Range)));
+    /// assert_eq!(lex.next(), Some(Ok(Token::Number(2.0))));
+    /// assert_eq!(lex.slice(), "2.");
+    /// ```
+    pub fn unbump(&mut self, n: usize) {
+        let offset = self.token_end.checked_sub(n).expect("Invalid Lexer unbump");
+
+        assert!(
+            offset > self.token_start && self.source.is_boundary(offset),
+            "Invalid Lexer unbump",
+        );
+
+        self.token_end = offset;
+    }
+
+    /// Bumps the end of currently lexed token up to, but not including, the first
+    /// occurrence of `needle` in the remainder.
+    ///
+    /// Returns `false` and leaves the token untouched if `needle` could not be found.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the occurrence of `needle` starts in the middle of an UTF-8 code point
+    /// (does not apply when lexing raw `&[u8]`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use logos::{Lexer, Logos};
+    ///
+    /// fn line_comment<'s>(lex: &mut Lexer<'s, Token<'s>>) -> &'s str {
+    ///     if !lex.bump_until(b'\n') {
+    ///         lex.bump(lex.remainder().len());
+    ///     }
+    ///     lex.slice()
+    /// }
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(skip r"\s+")]
+    /// enum Token<'s> {
+    ///     #[token("//", line_comment)]
+    ///     Comment(&'s str),
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Word,
+    /// }
+    ///
+    /// let mut lex = Token::lexer("// foo\nbar // baz");
+    ///
+    /// assert_eq!(lex.next(), Some(Ok(Token::Comment("// foo"))));
+    /// assert_eq!(lex.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lex.next(), Some(Ok(Token::Comment("// baz"))));
+    /// assert_eq!(lex.next(), None);
+    /// ```
+    pub fn bump_until<N: Needle>(&mut self, needle: N) -> bool {
+        match self.find_needle(self.token_end, needle.as_bytes()) {
+            Some(offset) => {
+                self.bump(offset - self.token_end);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Bumps the end of currently lexed token past the first occurrence of `needle`
+    /// in the remainder, so that the token ends with `needle`.
+    ///
+    /// Returns `false` and leaves the token untouched if `needle` could not be found.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the occurrence of `needle` starts or ends in the middle of an UTF-8
+    /// code point (does not apply when lexing raw `&[u8]`).
+    pub fn bump_past<N: Needle>(&mut self, needle: N) -> bool {
+        let needle = needle.as_bytes();
+
+        match self.find_needle(self.token_end, needle) {
+            Some(offset) => {
+                self.bump(offset + needle.len() - self.token_end);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Bumps the end of currently lexed token for as long as `predicate` returns
+    /// `true` for the next byte of the remainder.
+    ///
+    /// Returns `false` if no bytes were consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the first byte rejected by `predicate` is in the middle of an UTF-8
+    /// code point (does not apply when lexing raw `&[u8]`).
+    pub fn bump_while<P>(&mut self, mut predicate: P) -> bool
+    where
+        P: FnMut(u8) -> bool,
+    {
+        let mut offset = self.token_end;
+
+        while let Some(byte) = self.read_source::<u8>(offset) {
+            if !predicate(byte) {
+                break;
+            }
+            offset += 1;
+        }
+
+        let n = offset - self.token_end;
+
+        self.bump(n);
+        n != 0
+    }
+
+    /// Read a chunk of the source at `offset`, without unsafe code if the token derives
+    /// `#[logos(forbid_unsafe)]`, like the generated code does.
+    #[inline]
+    pub(crate) fn read_source<T>(&self, offset: usize) -> Option<T>
+    where
+        T: source::Chunk<'source>,
+    {
+        match Token::FORBID_UNSAFE {
+            true => self.source.read_safe(offset),
+            false => self.source.read(offset),
+        }
+    }
+
+    /// Find the offset of the first occurrence of `needle`, starting at `offset`.
+    pub(crate) fn find_needle(&self, mut offset: usize, needle: &[u8]) -> Option<usize> {
+        let Some((&first, rest)) = needle.split_first() else {
+            return Some(offset);
+        };
+
+        loop {
+            offset = self.find_byte(offset, first)?;
+
+            let matches = rest
+                .iter()
+                .enumerate()
+                .all(|(i, &byte)| self.read_source::<u8>(offset + 1 + i) == Some(byte));
+
+            if matches {
+                return Some(offset);
+            }
+            offset += 1;
+        }
+    }
+
+    /// Find the offset of the first occurrence of `byte`, starting at `offset`.
+    ///
+    /// Scans the source a word at a time, testing all 8 bytes of a chunk at once,
+    /// and falls back to reading single bytes near the end of the source.
+    fn find_byte(&self, mut offset: usize, byte: u8) -> Option<usize> {
+        const LO: u64 = u64::from_ne_bytes([0x01; 8]);
+        const HI: u64 = u64::from_ne_bytes([0x80; 8]);
+
+        let splat = LO * byte as u64;
+
+        while let Some(chunk) = self.read_source::<&[u8; 8]>(offset) {
+            let word = u64::from_ne_bytes(*chunk) ^ splat;
+
+            if word.wrapping_sub(LO) & !word & HI != 0 {
+                break;
+            }
+            offset += 8;
+        }
+
+        while let Some(b) = self.read_source::<u8>(offset) {
+            if b == byte {
+                return Some(offset);
+            }
+            offset += 1;
+        }
+
+        None
+    }
+}
+
+/// A byte pattern that can be searched for by [`Lexer::bump_until`] and
+/// [`Lexer::bump_past`].
+///
+/// This is implemented for a single `u8`, byte slices, byte arrays and string slices.
+pub trait Needle {
+    /// Bytes to search for.
+    fn as_bytes(&self) -> &[u8];
+}
+
+impl Needle for u8 {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        core::slice::from_ref(self)
+    }
+}
+
+impl Needle for &[u8] {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<const N: usize> Needle for &[u8; N] {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        &self[..]
+    }
+}
+
+impl Needle for &str {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+}
+
+/// Identifies the `#[token]`, `#[regex]` or `#[logos(skip)]` pattern that matched
+/// a token, see [`Lexer::rule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RuleId {
+    index: usize,
+    pattern: &'static str,
+}
+
+impl RuleId {
+    #[inline]
+    pub(crate) fn of<'source, Token: Logos<'source>>(index: u32) -> Self {
+        let index = index as usize;
+
+        RuleId {
+            index,
+            pattern: Token::PATTERNS[index],
+        }
+    }
+
+    /// Index of the pattern, in declaration order, counting `#[logos(skip)]`
+    /// patterns first.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The pattern as written in the attribute, without quotes. Non-ASCII bytes
+    /// of byte string patterns are escaped.
+    #[inline]
+    pub fn pattern(&self) -> &'static str {
+        self.pattern
+    }
 }
 
+/// Position of a [`Lexer`] saved by [`Lexer::checkpoint`], along with a snapshot
+/// of its `Extras`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint<S> {
+    token_start: usize,
+    token_end: usize,
+    rule: Option<u32>,
+    extras: S,
+    /// Number of warnings recorded when the checkpoint was made.
+    warnings: usize,
+}
+
+impl<S> Checkpoint<S> {
+    /// Span of the current token when the checkpoint was made.
+    #[inline]
+    pub fn span(&self) -> Span {
+        self.token_start..self.token_end
+    }
+}
+
+/// `Extras` that can be saved and restored by [`Lexer::checkpoint`] and [`Lexer::restore`].
+///
+/// This is implemented for `()`, primitive integers, `bool`, `char`, and tuples of those.
+///
+/// # Example
+///
+/// ```
+/// use logos::SnapshotExtras;
+///
+/// #[derive(Default)]
+/// struct Extras {
+///     line: usize,
+///     // Only grows, so it is enough to remember its length
+///     comments: Vec<String>,
+/// }
+///
+/// impl SnapshotExtras for Extras {
+///     type Snapshot = (usize, usize);
+///
+///     fn snapshot(&self) -> Self::Snapshot {
+///         (self.line, self.comments.len())
+///     }
+///
+///     fn restore(&mut self, (line, comments): Self::Snapshot) {
+///         self.line = line;
+///         self.comments.truncate(comments);
+///     }
+/// }
+/// ```
+pub trait SnapshotExtras {
+    /// Saved state of the `Extras`.
+    type Snapshot;
+
+    /// Save the current state.
+    fn snapshot(&self) -> Self::Snapshot;
+
+    /// Go back to a previously saved state.
+    fn restore(&mut self, snapshot: Self::Snapshot);
+}
+
+macro_rules! impl_snapshot_copy {
+    ($($ty:ty),*) => {
+        $(
+            impl SnapshotExtras for $ty {
+                type Snapshot = $ty;
+
+                #[inline]
+                fn snapshot(&self) -> $ty {
+                    *self
+                }
+
+                #[inline]
+                fn restore(&mut self, snapshot: $ty) {
+                    *self = snapshot;
+                }
+            }
+        )*
+    };
+}
+
+impl_snapshot_copy!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize
+);
+
+macro_rules! impl_snapshot_tuple {
+    ($($name:ident $index:tt),*) => {
+        impl<$($name: SnapshotExtras),*> SnapshotExtras for ($($name,)*) {
+            type Snapshot = ($($name::Snapshot,)*);
+
+            #[inline]
+            fn snapshot(&self) -> Self::Snapshot {
+                ($(self.$index.snapshot(),)*)
+            }
+
+            #[inline]
+            fn restore(&mut self, snapshot: Self::Snapshot) {
+                $(self.$index.restore(snapshot.$index);)*
+            }
+        }
+    };
+}
+
+impl_snapshot_tuple!(A 0);
+impl_snapshot_tuple!(A 0, B 1);
+impl_snapshot_tuple!(A 0, B 1, C 2);
+impl_snapshot_tuple!(A 0, B 1, C 2, D 3);
+
 impl<'source, Token> Clone for Lexer<'source, Token>
 where
     Token: Logos<'source> + Clone,
@@ -235,8 +1088,13 @@ where
 {
     fn clone(&self) -> Self {
         Lexer {
+            source: self.source,
+            is_prefix: self.is_prefix,
+            token_start: self.token_start,
+            token_end: self.token_end,
+            rule: self.rule,
+            warnings: self.warnings.clone(),
             extras: self.extras.clone(),
-            ..*self
         }
     }
 }
@@ -258,37 +1116,46 @@ where
 /// Iterator that pairs tokens with their position in the source.
 ///
 /// Look at [`Lexer::spanned`](./struct.Lexer.html#method.spanned) for documentation.
-pub struct SpannedIter<'source, Token: Logos<'source>> {
+pub struct SpannedIter<'source, Token: Logos<'source>, O: Offset = usize> {
     lexer: Lexer<'source, Token>,
+    offset: PhantomData<O>,
 }
 
 // deriving Clone doesn't infer the necessary `Token::Extras: Clone` bound
-impl<'source, Token> Clone for SpannedIter<'source, Token>
+impl<'source, Token, O> Clone for SpannedIter<'source, Token, O>
 where
     Token: Logos<'source> + Clone,
     Token::Extras: Clone,
+    O: Offset,
 {
     fn clone(&self) -> Self {
         SpannedIter {
             lexer: self.lexer.clone(),
+            offset: PhantomData,
         }
     }
 }
 
-impl<'source, Token> Iterator for SpannedIter<'source, Token>
+impl<'source, Token, O> Iterator for SpannedIter<'source, Token, O>
 where
     Token: Logos<'source>,
+    O: Offset,
 {
-    type Item = (Result<Token, Token::Error>, Span);
+    type Item = (Result<Token, Token::Error>, Range<O>);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.lexer.next().map(|token| (token, self.lexer.span()))
+        self.lexer.next().map(|token| {
+            let span = O::from_usize(self.lexer.token_start)..O::from_usize(self.lexer.token_end);
+
+            (token, span)
+        })
     }
 }
 
-impl<'source, Token> Deref for SpannedIter<'source, Token>
+impl<'source, Token, O> Deref for SpannedIter<'source, Token, O>
 where
     Token: Logos<'source>,
+    O: Offset,
 {
     type Target = Lexer<'source, Token>;
 
@@ -297,9 +1164,10 @@ where
     }
 }
 
-impl<'source, Token> DerefMut for SpannedIter<'source, Token>
+impl<'source, Token, O> DerefMut for SpannedIter<'source, Token, O>
 where
     Token: Logos<'source>,
+    O: Offset,
 {
     fn deref_mut(&mut self) -> &mut Lexer<'source, Token> {
         &mut self.lexer
@@ -327,6 +1195,14 @@ where
         self.source.read(offset)
     }
 
+    #[inline]
+    fn read_safe<Chunk>(&self, offset: usize) -> Option<Chunk>
+    where
+        Chunk: source::Chunk<'source>,
+    {
+        self.source.read_safe(offset)
+    }
+
     /// Reset `token_start` to `token_end`.
     #[inline]
     fn trivia(&mut self) {
@@ -345,6 +1221,11 @@ where
         self.token_end = offset;
     }
 
+    #[inline]
+    fn set_rule(&mut self, index: Option<u32>) {
+        self.rule = index;
+    }
+
     #[inline]
     fn offset(&self) -> usize {
         self.token_start
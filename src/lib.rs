@@ -26,17 +26,31 @@
 
 extern crate core;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 use core::fmt::Debug;
 #[cfg(feature = "export_derive")]
 pub use logos_derive::Logos;
 
+#[cfg(feature = "std")]
+mod buffer;
+pub mod callbacks;
+mod intern;
 mod lexer;
+mod owned;
 pub mod source;
 
 #[doc(hidden)]
 pub mod internal;
 
-pub use crate::lexer::{Lexer, Span, SpannedIter};
+#[cfg(feature = "std")]
+pub use crate::buffer::{TokenBuffer, TokenSlice};
+pub use crate::intern::Interner;
+pub use crate::lexer::{
+    Checkpoint, Lexer, Needle, Offset, RuleId, SnapshotExtras, Span, SpannedIter,
+};
+pub use crate::owned::{OwnedLexer, OwnedLogos, OwnedSpannedIter};
 pub use crate::source::Source;
 
 /// Trait implemented for an enum representing all tokens. You should never have
@@ -55,6 +69,21 @@ pub trait Logos<'source>: Sized {
     /// `#[logos(error = MyError)]`. Defaults to `()` if not set.
     type Error: Default + Clone + PartialEq + Debug + 'source;
 
+    /// Storage of the warnings recorded with [`Lexer::warn`], which is `()`
+    /// unless the warning type is set with `#[logos(warning = MyWarning)]`.
+    #[doc(hidden)]
+    type WarningStore: internal::WarningStore;
+
+    /// Patterns of the token, in declaration order, counting `#[logos(skip)]`
+    /// patterns first. Used by [`Lexer::rule`].
+    #[doc(hidden)]
+    const PATTERNS: &'static [&'static str] = &[];
+
+    /// Set by `#[logos(forbid_unsafe)]`, makes [`Lexer`] methods use checked accesses
+    /// to the source, whatever the features of the crate.
+    #[doc(hidden)]
+    const FORBID_UNSAFE: bool = false;
+
     /// The heart of Logos. Called by the `Lexer`. The implementation for this function
     /// is generated by the `logos-derive` crate.
     fn lex(lexer: &mut Lexer<'source, Self>) -> Option<Result<Self, Self::Error>>;
@@ -78,6 +107,42 @@ pub trait Logos<'source>: Sized {
     }
 }
 
+/// Tokens whose callbacks can record non-fatal diagnostics with [`Lexer::warn`].
+///
+/// This is implemented by `#[derive(Logos)]` when the warning type is set with
+/// `#[logos(warning = MyWarning)]`. Only lexers of those tokens store warnings,
+/// which are carried over by [`Lexer::morph`] and [`Lexer::sublex`] to lexers of
+/// tokens with the same warning type.
+#[cfg(feature = "std")]
+pub trait Warnings<'source>: Logos<'source> {
+    /// Type of the diagnostics recorded by [`Lexer::warn`].
+    type Warning: Clone + Debug + 'static;
+
+    #[doc(hidden)]
+    fn warning_list(store: &Self::WarningStore) -> &internal::WarningList<Self::Warning>;
+
+    #[doc(hidden)]
+    fn warning_list_mut(
+        store: &mut Self::WarningStore,
+    ) -> &mut internal::WarningList<Self::Warning>;
+}
+
+/// Tokens that can be split into a compact kind and a payload, which is how they
+/// are stored in a [`TokenBuffer`](./struct.TokenBuffer.html).
+///
+/// This is implemented by `#[derive(Logos)]` with `#[logos(kind)]`, numbering variants
+/// in declaration order.
+pub trait TokenKind: Sized {
+    /// Kind used by a `TokenBuffer` for errors, never returned by [`TokenKind::kind`].
+    const ERROR: u16 = u16::MAX;
+
+    /// Index of the variant of this token.
+    fn kind(&self) -> u16;
+
+    /// Token of a variant without fields, or `None` if the variant of `kind` has fields.
+    fn from_kind(kind: u16) -> Option<Self>;
+}
+
 /// Type that can be returned from a callback, informing the `Lexer`, to skip
 /// current token match. See also [`logos::skip`](./fn.skip.html).
 ///
@@ -225,7 +290,118 @@ pub enum FilterResult<T, E> {
     Error(E),
 }
 
+/// What the lexer has just matched, passed to the hook set with
+/// `#[logos(on_token = path::to::hook)]`.
+///
+/// The hook is called with the lexer and this event after every token, error and skip,
+/// while the span of the lexer still points at the matched input.
+///
+/// # Example
+///
+/// ```rust
+/// use logos::{Lexer, Logos, TokenEvent};
+///
+/// #[derive(Default)]
+/// struct Extras {
+///     depth: usize,
+///     skipped: usize,
+/// }
+///
+/// fn track(lex: &mut Lexer<Token>, event: TokenEvent<Token>) {
+///     match event {
+///         TokenEvent::Token(Token::Open) => lex.extras.depth += 1,
+///         TokenEvent::Token(Token::Close) => lex.extras.depth -= 1,
+///         TokenEvent::Skip(_) => lex.extras.skipped += lex.slice().len(),
+///         _ => (),
+///     }
+/// }
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(skip " +")]
+/// #[logos(extras = Extras)]
+/// #[logos(on_token = track)]
+/// enum Token {
+///     #[token("(")]
+///     Open,
+///
+///     #[token(")")]
+///     Close,
+/// }
+///
+/// let mut lex = Token::lexer("( (  )");
+///
+/// assert_eq!(lex.next(), Some(Ok(Token::Open)));
+/// assert_eq!(lex.next(), Some(Ok(Token::Open)));
+/// assert_eq!(lex.next(), Some(Ok(Token::Close)));
+/// assert_eq!(lex.extras.depth, 1);
+/// assert_eq!(lex.extras.skipped, 3);
+/// ```
+pub enum TokenEvent<'t, 'source, Token: Logos<'source>> {
+    /// A token is about to be emitted.
+    Token(&'t Token),
+    /// An error is about to be emitted.
+    Error(&'t Token::Error),
+    /// The matched input is about to be skipped. Holds the index of the pattern that
+    /// matched it, in declaration order, counting `#[logos(skip)]` patterns first, like
+    /// [`RuleId::index`].
+    Skip(usize),
+}
+
+/// Error for a token growing past the length limit set with `#[logos(max_token_len = N)]`,
+/// or with the `max_len` of its pattern. The lexer stops reading the token once the limit is
+/// reached, and the span of the error covers the input read so far.
+///
+/// Lexers with a length limit need their error type to implement `From<TokenTooLong>`,
+/// which the default `()` error does.
+///
+/// # Example
+///
+/// ```rust
+/// use logos::{Logos, TokenTooLong};
+///
+/// #[derive(Default, Debug, Clone, PartialEq)]
+/// enum LexingError {
+///     TooLong(usize),
+///     #[default]
+///     Other,
+/// }
+///
+/// impl From<TokenTooLong> for LexingError {
+///     fn from(err: TokenTooLong) -> Self {
+///         LexingError::TooLong(err.limit)
+///     }
+/// }
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(skip " +", error = LexingError)]
+/// #[logos(max_token_len = 8)]
+/// enum Token {
+///     #[regex("[a-z]+")]
+///     Word,
+///
+///     #[regex(r#""[^"]*""#, max_len = 16)]
+///     String,
+/// }
+///
+/// let mut lex = Token::lexer(r#"short "a long string" unreasonably"#);
+///
+/// assert_eq!(lex.next(), Some(Ok(Token::Word)));
+/// assert_eq!(lex.next(), Some(Ok(Token::String)));
+/// assert_eq!(lex.next(), Some(Err(LexingError::TooLong(8))));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenTooLong {
+    /// The length limit of the token, in bytes.
+    pub limit: usize,
+}
+
+impl From<TokenTooLong> for () {
+    #[inline]
+    fn from(_: TokenTooLong) {}
+}
+
 /// Predefined callback that will inform the `Lexer` to skip a definition.
+/// See the [`callbacks`](./callbacks/index.html) module for more stock callbacks.
 ///
 /// # Example
 ///
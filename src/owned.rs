@@ -0,0 +1,270 @@
+use crate::internal::WarningStore;
+use crate::lexer::{Lexer, RuleId, Span};
+use crate::source::Source;
+use crate::Logos;
+#[cfg(feature = "std")]
+use crate::Warnings;
+
+use core::ops::{Deref, DerefMut, Range};
+
+/// Token types that don't borrow from the source, and thus can be produced by an
+/// [`OwnedLexer`].
+///
+/// This is implemented automatically for any `Token` implementing `Logos<'source>`
+/// for every lifetime `'source`, which is the case for `#[derive(Logos)]` enums
+/// without a source lifetime.
+pub trait OwnedLogos:
+    for<'source> Logos<
+    'source,
+    Source = Self::OwnedSource,
+    Extras = Self::OwnedExtras,
+    Error = Self::OwnedError,
+    WarningStore = Self::OwnedWarningStore,
+>
+{
+    /// Same as [`Logos::Source`].
+    type OwnedSource: Source + ?Sized + 'static;
+
+    /// Same as [`Logos::Extras`].
+    type OwnedExtras;
+
+    /// Same as [`Logos::Error`].
+    type OwnedError;
+
+    #[doc(hidden)]
+    type OwnedWarningStore: WarningStore;
+}
+
+impl<Token, S, X, E, W> OwnedLogos for Token
+where
+    Token: for<'source> Logos<'source, Source = S, Extras = X, Error = E, WarningStore = W>,
+    S: Source + ?Sized + 'static,
+    W: WarningStore,
+{
+    type OwnedSource = S;
+    type OwnedExtras = X;
+    type OwnedError = E;
+    type OwnedWarningStore = W;
+}
+
+/// A lexer owning its input, such as a `String`, `Box<str>`, `Arc<str>` or `Vec<u8>`.
+///
+/// Unlike [`Lexer`], it doesn't borrow the source, which makes it easy to store next
+/// to other long-lived state. It can only produce tokens that don't borrow from the
+/// source either, see [`OwnedLogos`].
+///
+/// # Example
+///
+/// ```
+/// use logos::{Logos, OwnedLexer};
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(skip r"\s+")]
+/// enum Token {
+///     #[regex("[a-z]+")]
+///     Word,
+///
+///     #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+///     Number(u64),
+/// }
+///
+/// struct Parser {
+///     lexer: OwnedLexer<Token, String>,
+/// }
+///
+/// let mut parser = Parser {
+///     lexer: OwnedLexer::new(String::from("foo 42")),
+/// };
+///
+/// assert_eq!(parser.lexer.next(), Some(Ok(Token::Word)));
+/// assert_eq!(parser.lexer.slice(), "foo");
+/// assert_eq!(parser.lexer.next(), Some(Ok(Token::Number(42))));
+/// assert_eq!(parser.lexer.span(), 4..6);
+/// assert_eq!(parser.lexer.next(), None);
+/// ```
+pub struct OwnedLexer<Token: OwnedLogos, S> {
+    source: S,
+
+    token_start: usize,
+    token_end: usize,
+    rule: Option<u32>,
+
+    warnings: Token::OwnedWarningStore,
+
+    /// Only `None` while a token is being lexed.
+    extras: Option<Token::OwnedExtras>,
+}
+
+impl<Token, S> OwnedLexer<Token, S>
+where
+    Token: OwnedLogos,
+    S: Deref<Target = Token::OwnedSource>,
+{
+    /// Create a new `OwnedLexer`.
+    pub fn new(source: S) -> Self
+    where
+        Token::OwnedExtras: Default,
+    {
+        Self::with_extras(source, Default::default())
+    }
+
+    /// Create a new `OwnedLexer` with the provided `Extras`.
+    pub fn with_extras(source: S, extras: Token::OwnedExtras) -> Self {
+        OwnedLexer {
+            source,
+            token_start: 0,
+            token_end: 0,
+            rule: None,
+            warnings: Default::default(),
+            extras: Some(extras),
+        }
+    }
+
+    /// Source from which this lexer is reading tokens.
+    #[inline]
+    pub fn source(&self) -> &Token::OwnedSource {
+        &self.source
+    }
+
+    /// Consume the lexer, returning the owned source.
+    pub fn into_source(self) -> S {
+        self.source
+    }
+
+    /// Extras associated with the `Token`.
+    #[inline]
+    pub fn extras(&self) -> &Token::OwnedExtras {
+        self.extras
+            .as_ref()
+            .expect("Extras are only taken while lexing")
+    }
+
+    /// Mutable access to the extras associated with the `Token`.
+    #[inline]
+    pub fn extras_mut(&mut self) -> &mut Token::OwnedExtras {
+        self.extras
+            .as_mut()
+            .expect("Extras are only taken while lexing")
+    }
+
+    /// Take all warnings recorded by callbacks so far, see [`Lexer::warn`].
+    #[cfg(feature = "std")]
+    pub fn take_warnings<W>(&mut self) -> Vec<(W, Span)>
+    where
+        Token: for<'source> Warnings<'source, Warning = W>,
+    {
+        Token::warning_list_mut(&mut self.warnings).take()
+    }
+
+    /// Wrap the `OwnedLexer` in an [`Iterator`] that produces tuples of
+    /// `(Token, `[`Span`]`)`, see [`Lexer::spanned`].
+    #[inline]
+    pub fn spanned(self) -> OwnedSpannedIter<Token, S> {
+        OwnedSpannedIter { lexer: self }
+    }
+
+    /// Get the pattern that matched the current token, see [`Lexer::rule`].
+    #[inline]
+    pub fn rule(&self) -> Option<RuleId> {
+        self.rule.map(RuleId::of::<Token>)
+    }
+
+    /// Get the range for the current token in `Source`.
+    #[inline]
+    pub fn span(&self) -> Span {
+        self.token_start..self.token_end
+    }
+
+    /// Get a slice of the current token, borrowed from this lexer.
+    #[inline]
+    pub fn slice(&self) -> <Token::OwnedSource as Source>::Slice<'_> {
+        self.slice_at(self.token_start..self.token_end)
+    }
+
+    /// Get a slice of remaining source, starting at the end of current token.
+    #[inline]
+    pub fn remainder(&self) -> <Token::OwnedSource as Source>::Slice<'_> {
+        self.slice_at(self.token_end..self.source.len())
+    }
+
+    fn slice_at(&self, range: Range<usize>) -> <Token::OwnedSource as Source>::Slice<'_> {
+        // `S::deref` isn't guaranteed to return the same buffer every time,
+        // so the offsets of the last token have to be checked again
+        self.source
+            .slice(range)
+            .expect("Source of OwnedLexer changed while lexing")
+    }
+}
+
+impl<Token, S> Iterator for OwnedLexer<Token, S>
+where
+    Token: OwnedLogos,
+    S: Deref<Target = Token::OwnedSource>,
+{
+    type Item = Result<Token, Token::OwnedError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let source = &*self.source;
+
+        assert!(
+            source.is_boundary(self.token_end),
+            "Source of OwnedLexer changed while lexing",
+        );
+
+        let mut lex = Lexer::<Token> {
+            source,
+            is_prefix: false,
+            token_start: self.token_end,
+            token_end: self.token_end,
+            rule: None,
+            warnings: core::mem::take(&mut self.warnings),
+            extras: self
+                .extras
+                .take()
+                .expect("Extras are only taken while lexing"),
+        };
+
+        let token = Token::lex(&mut lex);
+
+        self.token_start = lex.token_start;
+        self.token_end = lex.token_end;
+        self.rule = lex.rule;
+        self.warnings = lex.warnings;
+        self.extras = Some(lex.extras);
+
+        token
+    }
+}
+
+/// Iterator that pairs tokens with their position in the source.
+///
+/// Look at [`OwnedLexer::spanned`] for documentation.
+pub struct OwnedSpannedIter<Token: OwnedLogos, S> {
+    lexer: OwnedLexer<Token, S>,
+}
+
+impl<Token, S> Iterator for OwnedSpannedIter<Token, S>
+where
+    Token: OwnedLogos,
+    S: Deref<Target = Token::OwnedSource>,
+{
+    type Item = (Result<Token, Token::OwnedError>, Span);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lexer.next().map(|token| (token, self.lexer.span()))
+    }
+}
+
+impl<Token: OwnedLogos, S> Deref for OwnedSpannedIter<Token, S> {
+    type Target = OwnedLexer<Token, S>;
+
+    fn deref(&self) -> &OwnedLexer<Token, S> {
+        &self.lexer
+    }
+}
+
+impl<Token: OwnedLogos, S> DerefMut for OwnedSpannedIter<Token, S> {
+    fn deref_mut(&mut self) -> &mut OwnedLexer<Token, S> {
+        &mut self.lexer
+    }
+}
@@ -3,6 +3,8 @@
 //! Most notable are:
 //! * `Source` - implemented by default for `&str`, `&[u8]` and wrapper types, used by the `Lexer`.
 //! * `Slice` - slices of `Source`, returned by `Lexer::slice`.
+//! * `Padded` - a `str` or `[u8]` source followed by a sentinel byte, so lexers can read it without
+//!   bounds checks.
 
 use core::fmt::Debug;
 use core::ops::{Deref, Range};
@@ -48,6 +50,19 @@ pub trait Source {
     where
         Chunk: self::Chunk<'a>;
 
+    /// Read a chunk of bytes like [`Source::read`], without using unsafe code.
+    ///
+    /// This is what lexers deriving `#[logos(forbid_unsafe)]` read with. Defaults to
+    /// calling [`Source::read`], so custom sources that read with unsafe code should
+    /// override it for that attribute to hold.
+    #[inline]
+    fn read_safe<'a, Chunk>(&'a self, offset: usize) -> Option<Chunk>
+    where
+        Chunk: self::Chunk<'a>,
+    {
+        self.read(offset)
+    }
+
     /// Get a slice of the source at given range. This is analogous to
     /// `slice::get(range)`.
     ///
@@ -119,10 +134,15 @@ impl Source for str {
         }
 
         #[cfg(feature = "forbid_unsafe")]
-        Chunk::from_slice(
-            self.as_bytes()
-                .slice(offset..offset.checked_add(Chunk::SIZE)?)?,
-        )
+        self.read_safe(offset)
+    }
+
+    #[inline]
+    fn read_safe<'a, Chunk>(&'a self, offset: usize) -> Option<Chunk>
+    where
+        Chunk: self::Chunk<'a>,
+    {
+        self.as_bytes().read_safe(offset)
     }
 
     #[inline]
@@ -182,6 +202,14 @@ impl Source for [u8] {
         }
 
         #[cfg(feature = "forbid_unsafe")]
+        self.read_safe(offset)
+    }
+
+    #[inline]
+    fn read_safe<'a, Chunk>(&'a self, offset: usize) -> Option<Chunk>
+    where
+        Chunk: self::Chunk<'a>,
+    {
         Chunk::from_slice(self.slice(offset..offset.checked_add(Chunk::SIZE)?)?)
     }
 
@@ -230,6 +258,13 @@ where
         self.deref().read(offset)
     }
 
+    fn read_safe<'a, Chunk>(&'a self, offset: usize) -> Option<Chunk>
+    where
+        Chunk: self::Chunk<'a>,
+    {
+        self.deref().read_safe(offset)
+    }
+
     fn slice(&self, range: Range<usize>) -> Option<Self::Slice<'_>> {
         self.deref().slice(range)
     }
@@ -248,6 +283,156 @@ where
     }
 }
 
+/// A `str` or `[u8]` source followed by a sentinel `NUL` byte.
+///
+/// Lexers deriving `#[logos(padded)]` lex from this source. Every byte they read is either
+/// part of the source or the sentinel, so they read without checking the length of the
+/// source, and only check whether they reached its end when they read a `NUL` byte. With
+/// the `forbid_unsafe` feature, or `#[logos(forbid_unsafe)]`, bytes are read with bounds
+/// checks instead.
+///
+/// The sentinel is not part of the source: it doesn't show up in slices, and the length of
+/// the source doesn't count it.
+///
+/// ```rust
+/// use logos::source::{Padded, Source};
+///
+/// let text = "let x = 42;\0";
+/// let source = Padded::<str>::new(text).unwrap();
+///
+/// assert_eq!(source.len(), 11);
+/// assert_eq!(source.as_str(), "let x = 42;");
+///
+/// // The sentinel must be there
+/// assert!(Padded::<str>::new("let x = 42;").is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Padded<'a, T: ?Sized> {
+    /// The source, including the sentinel
+    padded: &'a T,
+}
+
+impl<'a> Padded<'a, str> {
+    /// Wraps a string ending with a `NUL` sentinel byte. The sentinel is not part of the
+    /// source. Returns `None` if the string doesn't end with a `NUL` byte.
+    #[inline]
+    pub fn new(padded: &'a str) -> Option<Self> {
+        match padded.ends_with('\0') {
+            true => Some(Padded { padded }),
+            false => None,
+        }
+    }
+
+    /// The source without the sentinel.
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        &self.padded[..self.padded.len() - 1]
+    }
+}
+
+impl<'a> Padded<'a, [u8]> {
+    /// Wraps bytes ending with a `NUL` sentinel byte. The sentinel is not part of the
+    /// source. Returns `None` if the bytes don't end with a `NUL` byte.
+    #[inline]
+    pub fn new(padded: &'a [u8]) -> Option<Self> {
+        match padded.last() {
+            Some(0) => Some(Padded { padded }),
+            _ => None,
+        }
+    }
+
+    /// The source without the sentinel.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        &self.padded[..self.padded.len() - 1]
+    }
+}
+
+/// Implements `Source` for a `Padded` source by forwarding to the source without the sentinel,
+/// and the unchecked read of single bytes.
+macro_rules! impl_padded_source {
+    ($ty:ty, $as_source:ident, $as_bytes:ident) => {
+        impl Padded<'_, $ty> {
+            /// Reads a single byte, returning `None` at the end of the source like
+            /// [`Source::read`]. Only the sentinel value is compared with the length of
+            /// the source. Not available with the `forbid_unsafe` feature.
+            ///
+            /// # Safety
+            ///
+            /// `offset` must not be greater than the length of the source.
+            #[cfg(not(feature = "forbid_unsafe"))]
+            #[inline]
+            pub unsafe fn read_byte_unchecked(&self, offset: usize) -> Option<u8> {
+                debug_assert!(
+                    offset <= self.len(),
+                    "Reading out of bounds {} for {}!",
+                    offset,
+                    self.len()
+                );
+
+                // SAFETY: the caller guarantees that `offset` is at most the length of the
+                // source, and the sentinel follows the source.
+                match *self.padded.$as_bytes().get_unchecked(offset) {
+                    0 if offset == self.len() => None,
+                    byte => Some(byte),
+                }
+            }
+        }
+
+        impl Source for Padded<'_, $ty> {
+            type Slice<'b>
+                = &'b $ty
+            where
+                Self: 'b;
+
+            #[inline]
+            fn len(&self) -> usize {
+                self.padded.len() - 1
+            }
+
+            #[inline]
+            fn read<'b, Chunk>(&'b self, offset: usize) -> Option<Chunk>
+            where
+                Chunk: self::Chunk<'b>,
+            {
+                <$ty as Source>::read(self.$as_source(), offset)
+            }
+
+            #[inline]
+            fn read_safe<'b, Chunk>(&'b self, offset: usize) -> Option<Chunk>
+            where
+                Chunk: self::Chunk<'b>,
+            {
+                <$ty as Source>::read_safe(self.$as_source(), offset)
+            }
+
+            #[inline]
+            fn slice(&self, range: Range<usize>) -> Option<&$ty> {
+                <$ty as Source>::slice(self.$as_source(), range)
+            }
+
+            #[cfg(not(feature = "forbid_unsafe"))]
+            #[inline]
+            unsafe fn slice_unchecked(&self, range: Range<usize>) -> &$ty {
+                <$ty as Source>::slice_unchecked(self.$as_source(), range)
+            }
+
+            #[inline]
+            fn find_boundary(&self, index: usize) -> usize {
+                <$ty as Source>::find_boundary(self.$as_source(), index)
+            }
+
+            #[inline]
+            fn is_boundary(&self, index: usize) -> bool {
+                <$ty as Source>::is_boundary(self.$as_source(), index)
+            }
+        }
+    };
+}
+
+impl_padded_source!(str, as_str, as_bytes);
+impl_padded_source!([u8], as_bytes, as_ref);
+
 /// A fixed, statically sized chunk of data that can be read from the `Source`.
 ///
 /// This is implemented for `u8`, as well as byte arrays `&[u8; 1]` to `&[u8; 32]`.
@@ -265,7 +450,6 @@ pub trait Chunk<'source>: Sized + Copy + PartialEq + Eq {
 
     /// Create a chunk from a slice.
     /// Returns None if the slice is not long enough to produce the chunk.
-    #[cfg(feature = "forbid_unsafe")]
     fn from_slice(s: &'source [u8]) -> Option<Self>;
 }
 
@@ -280,7 +464,6 @@ impl<'source> Chunk<'source> for u8 {
     }
 
     #[inline]
-    #[cfg(feature = "forbid_unsafe")]
     fn from_slice(s: &'source [u8]) -> Option<Self> {
         s.first().copied()
     }
@@ -296,7 +479,6 @@ impl<'source, const N: usize> Chunk<'source> for &'source [u8; N] {
     }
 
     #[inline]
-    #[cfg(feature = "forbid_unsafe")]
     fn from_slice(s: &'source [u8]) -> Option<Self> {
         s.slice(0..Self::SIZE).and_then(|x| x.try_into().ok())
     }
@@ -0,0 +1,105 @@
+use logos::Logos;
+use std::collections::HashMap;
+
+#[derive(Default, Debug)]
+struct Interner {
+    symbols: HashMap<String, u32>,
+}
+
+impl Interner {
+    fn intern(&mut self, name: &str) -> u32 {
+        let next = self.symbols.len() as u32;
+
+        *self.symbols.entry(name.to_owned()).or_insert(next)
+    }
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(skip " +")]
+#[logos(extras = &'e mut Interner)]
+enum Token {
+    #[regex("[a-z]+", |lex| lex.extras.intern(lex.slice()))]
+    Ident(u32),
+}
+
+#[test]
+fn intern_into_external_table() {
+    let mut interner = Interner::default();
+    let tokens: Vec<_> = Token::lexer_with_extras("foo bar foo", &mut interner).collect();
+
+    assert_eq!(
+        tokens,
+        [
+            Ok(Token::Ident(0)),
+            Ok(Token::Ident(1)),
+            Ok(Token::Ident(0))
+        ]
+    );
+
+    // The interner can be used again once the lexer is done
+    let tokens: Vec<_> = Token::lexer_with_extras("baz bar", &mut interner).collect();
+
+    assert_eq!(tokens, [Ok(Token::Ident(2)), Ok(Token::Ident(1))]);
+    assert_eq!(interner.symbols.len(), 3);
+}
+
+mod inferred_source_lifetime {
+    use super::Interner;
+    use logos::Logos;
+
+    // `'a` is the only lifetime the extras type doesn't borrow for, so it's the source lifetime
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(skip " +")]
+    #[logos(extras = (&'e mut Interner, &'e str))]
+    enum Token<'a, 'e> {
+        #[regex("[a-z]+", |lex| { (lex.slice(), lex.extras.0.intern(lex.slice())) })]
+        Ident((&'a str, u32)),
+
+        #[token("!", |lex| lex.extras.1)]
+        Bang(&'e str),
+    }
+
+    #[test]
+    fn borrow_source_and_extras() {
+        let mut interner = Interner::default();
+        let mut lex = Token::lexer_with_extras("a b a!", (&mut interner, "bang"));
+
+        assert_eq!(lex.next(), Some(Ok(Token::Ident(("a", 0)))));
+        assert_eq!(lex.next(), Some(Ok(Token::Ident(("b", 1)))));
+        assert_eq!(lex.next(), Some(Ok(Token::Ident(("a", 0)))));
+        assert_eq!(lex.next(), Some(Ok(Token::Bang("bang"))));
+    }
+}
+
+mod explicit_source_lifetime {
+    use logos::Logos;
+    use std::collections::HashMap;
+
+    type Keywords = HashMap<&'static str, String>;
+
+    fn keyword<'s, 'e>(lex: &mut logos::Lexer<'s, Token<'s, 'e>>) -> Option<&'e str> {
+        let keywords: &'e Keywords = lex.extras;
+        keywords.get(lex.slice()).map(String::as_str)
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(skip " +")]
+    #[logos(lifetime = 's)]
+    #[logos(extras = &'e Keywords)]
+    enum Token<'s, 'e> {
+        #[regex("[A-Z]+", keyword)]
+        Keyword(&'e str),
+
+        #[regex("[a-z]+")]
+        Ident(&'s str),
+    }
+
+    #[test]
+    fn named_source_lifetime() {
+        let keywords = Keywords::from([("IF", String::from("if"))]);
+        let source = String::from("IF x");
+        let tokens: Vec<_> = Token::lexer_with_extras(&source, &keywords).collect();
+
+        assert_eq!(tokens, [Ok(Token::Keyword("if")), Ok(Token::Ident("x"))]);
+    }
+}
@@ -0,0 +1,117 @@
+use logos::{Logos, TokenBuffer, TokenKind};
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(skip r"[ \t\n\f]+")]
+#[logos(kind)]
+enum Token<'s> {
+    #[regex("[a-z]+")]
+    Ident(&'s str),
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u32),
+
+    #[token("(")]
+    LParen,
+
+    #[token(")")]
+    RParen,
+}
+
+const SOURCE: &str = "(add 1 (mul 2 x)) ?";
+
+#[test]
+fn matches_spanned() {
+    let buffer = Token::lexer(SOURCE).collect_buffer();
+    let spanned: Vec<_> = Token::lexer(SOURCE).spanned().collect();
+
+    assert_eq!(buffer.len(), spanned.len());
+    assert!(buffer.iter().eq(spanned.iter().cloned()));
+
+    // Errors are kept in place
+    assert_eq!(buffer.get(9), Some(Err(())));
+    assert_eq!(buffer.kind(9), Some(Token::ERROR));
+    assert_eq!(buffer.slice(9), Some("?"));
+}
+
+#[test]
+fn find() {
+    let buffer = Token::lexer(SOURCE).collect_buffer();
+
+    assert_eq!(buffer.find(0), Some(0));
+    assert_eq!(buffer.find(1), Some(1));
+    assert_eq!(buffer.find(3), Some(1));
+    assert_eq!(buffer.find(4), None);
+    assert_eq!(buffer.find(5), Some(2));
+    assert_eq!(buffer.find(16), Some(8));
+    assert_eq!(buffer.find(17), None);
+    assert_eq!(buffer.find(18), Some(9));
+    assert_eq!(buffer.find(19), None);
+    assert_eq!(buffer.find(1000), None);
+
+    let empty = Token::lexer("  ").collect_buffer();
+
+    assert!(empty.is_empty());
+    assert_eq!(empty.find(0), None);
+    assert_eq!(empty.span(0), None);
+}
+
+#[test]
+fn u32_offsets() {
+    let buffer: TokenBuffer<Token, u32> = TokenBuffer::from_lexer(Token::lexer(SOURCE));
+
+    assert_eq!(
+        buffer.offsets(),
+        &[
+            [0, 1],
+            [1, 4],
+            [5, 6],
+            [7, 8],
+            [8, 11],
+            [12, 13],
+            [14, 15],
+            [15, 16],
+            [16, 17],
+            [18, 19]
+        ]
+    );
+    assert_eq!(buffer.get(1), Some(Ok(Token::Ident("add"))));
+    assert_eq!(buffer.span(4), Some(8..11));
+}
+
+#[test]
+fn ranges() {
+    let buffer = Token::lexer(SOURCE).collect_buffer();
+    let inner = buffer.range(3..9);
+
+    assert_eq!(inner.len(), 6);
+    assert_eq!(inner.get(0), Some(Ok(Token::LParen)));
+    assert_eq!(inner.get(1), Some(Ok(Token::Ident("mul"))));
+    assert_eq!(inner.get(5), Some(Ok(Token::RParen)));
+    assert_eq!(inner.span(1), Some(8..11));
+    assert_eq!(inner.find(12), Some(2));
+    assert_eq!(inner.find(1), None);
+
+    let args = inner.range(2..4);
+
+    assert_eq!(
+        args.iter().map(|(token, _)| token).collect::<Vec<_>>(),
+        [Ok(Token::Number(2)), Ok(Token::Ident("x"))]
+    );
+    assert_eq!(args.get(2), None);
+    assert_eq!(args.slice(1), Some("x"));
+    assert_eq!(args.find(14), Some(1));
+}
+
+#[test]
+fn kinds() {
+    let buffer = Token::lexer(SOURCE).collect_buffer();
+
+    // Variants are numbered in declaration order
+    assert_eq!(Token::Ident("").kind(), 0);
+    assert_eq!(Token::LParen.kind(), 2);
+    assert_eq!(Token::from_kind(3), Some(Token::RParen));
+    assert_eq!(Token::from_kind(1), None);
+    assert_eq!(Token::from_kind(4), None);
+
+    assert_eq!(buffer.kinds(), &[2, 0, 1, 2, 0, 1, 0, 3, 3, Token::ERROR]);
+}
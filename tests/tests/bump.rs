@@ -0,0 +1,263 @@
+use logos::{Lexer, Logos};
+use tests::assert_lex;
+
+mod bump_until {
+    use super::*;
+
+    fn line_comment<'s>(lex: &mut Lexer<'s, Token<'s>>) -> &'s str {
+        if !lex.bump_until(b'\n') {
+            lex.bump(lex.remainder().len());
+        }
+        lex.slice()
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(skip r"\s+")]
+    enum Token<'s> {
+        #[token("#", line_comment)]
+        Comment(&'s str),
+
+        #[regex("[a-z]+")]
+        Word,
+    }
+
+    #[test]
+    fn until_byte() {
+        assert_lex(
+            "foo # comment ü\nbar #€ to the end",
+            &[
+                (Ok(Token::Word), "foo", 0..3),
+                (Ok(Token::Comment("# comment ü")), "# comment ü", 4..16),
+                (Ok(Token::Word), "bar", 17..20),
+                (Ok(Token::Comment("#€ to the end")), "#€ to the end", 21..36),
+            ],
+        );
+    }
+
+    #[test]
+    fn long_input() {
+        let source = format!("#{}\nfoo", "a".repeat(1000));
+
+        let mut lex = Token::lexer(&source);
+
+        assert_eq!(lex.next(), Some(Ok(Token::Comment(&source[..1001]))));
+        assert_eq!(lex.next(), Some(Ok(Token::Word)));
+        assert_eq!(lex.next(), None);
+    }
+}
+
+mod bump_past {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(skip r"\s+")]
+    enum Token {
+        #[token("/*", |lex| lex.bump_past("*/"))]
+        Comment,
+
+        #[token("<<", |lex| lex.bump_past(b">>"))]
+        Quote,
+
+        #[regex("[a-z]+")]
+        Word,
+    }
+
+    #[test]
+    fn past_needle() {
+        assert_lex(
+            "foo /* * / */ bar <<>>> /* ** *** */",
+            &[
+                (Ok(Token::Word), "foo", 0..3),
+                (Ok(Token::Comment), "/* * / */", 4..13),
+                (Ok(Token::Word), "bar", 14..17),
+                (Ok(Token::Quote), "<<>>", 18..22),
+                (Err(()), ">", 22..23),
+                (Ok(Token::Comment), "/* ** *** */", 24..36),
+            ],
+        );
+    }
+
+    #[test]
+    fn unterminated() {
+        assert_lex(
+            "/* foo * /",
+            &[
+                (Err(()), "/*", 0..2),
+                (Ok(Token::Word), "foo", 3..6),
+                (Err(()), "*", 7..8),
+                (Err(()), "/", 9..10),
+            ],
+        );
+    }
+}
+
+mod bump_while {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(skip r"\s+")]
+    enum Token {
+        #[token("0x", |lex| lex.bump_while(|b| b.is_ascii_hexdigit()))]
+        Hex,
+
+        #[regex("[a-z]+")]
+        Word,
+    }
+
+    #[test]
+    fn while_predicate() {
+        assert_lex(
+            "0xdeadBEEF zz 0x 0xff",
+            &[
+                (Ok(Token::Hex), "0xdeadBEEF", 0..10),
+                (Ok(Token::Word), "zz", 11..13),
+                (Err(()), "0x", 14..16),
+                (Ok(Token::Hex), "0xff", 17..21),
+            ],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid Lexer bump")]
+    fn not_on_boundary() {
+        #[derive(Logos, Debug, PartialEq)]
+        enum Token {
+            #[token("#", |lex| lex.bump_while(|b| b != 0x82))]
+            Hash,
+        }
+
+        let _ = Token::lexer("#€").next();
+    }
+}
+
+mod unbump {
+    use super::*;
+
+    fn number(lex: &mut Lexer<Token>) {
+        if lex.slice().ends_with('.') && lex.remainder().starts_with(['.', '_']) {
+            lex.unbump(1);
+        }
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    enum Token {
+        #[regex(r"[0-9]+(\.[0-9]*)?", number)]
+        Number,
+
+        #[token("..")]
+        Range,
+
+        #[token("._")]
+        Field,
+    }
+
+    #[test]
+    fn give_back() {
+        assert_lex(
+            "1..2._3.4.",
+            &[
+                (Ok(Token::Number), "1", 0..1),
+                (Ok(Token::Range), "..", 1..3),
+                (Ok(Token::Number), "2", 3..4),
+                (Ok(Token::Field), "._", 4..6),
+                (Ok(Token::Number), "3.4", 6..9),
+                (Err(()), ".", 9..10),
+            ],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid Lexer unbump")]
+    fn whole_token() {
+        #[derive(Logos, Debug, PartialEq)]
+        enum Token {
+            #[token("ab", |lex| lex.unbump(2))]
+            Ab,
+        }
+
+        let _ = Token::lexer("ab").next();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid Lexer unbump")]
+    fn not_on_boundary() {
+        #[derive(Logos, Debug, PartialEq)]
+        enum Token {
+            #[token("€", |lex| lex.unbump(1))]
+            Euro,
+        }
+
+        let _ = Token::lexer("€").next();
+    }
+}
+
+mod set_end {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(skip r"\s+")]
+    enum Token {
+        // Keep only the name of a `name=value` pair
+        #[regex("[a-z]+=[a-z]*", |lex| {
+            let value = lex.slice().len() - lex.slice().find('=').unwrap();
+            lex.set_end(lex.source().len() - lex.remainder().len() - value);
+        })]
+        Name,
+
+        #[token("=")]
+        Eq,
+
+        #[regex("[a-z]+")]
+        Word,
+    }
+
+    #[test]
+    fn shrink() {
+        assert_lex(
+            "foo=bar baz=",
+            &[
+                (Ok(Token::Name), "foo", 0..3),
+                (Ok(Token::Eq), "=", 3..4),
+                (Ok(Token::Word), "bar", 4..7),
+                (Ok(Token::Name), "baz", 8..11),
+                (Ok(Token::Eq), "=", 11..12),
+            ],
+        );
+    }
+
+    #[test]
+    fn grow() {
+        #[derive(Logos, Debug, PartialEq)]
+        enum Token {
+            #[token("<", |lex| lex.set_end(lex.source().len()))]
+            Rest,
+        }
+
+        assert_lex("<abc", &[(Ok(Token::Rest), "<abc", 0..4)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid Lexer end")]
+    fn before_start() {
+        #[derive(Logos, Debug, PartialEq)]
+        #[logos(skip " ")]
+        enum Token {
+            #[token("a", |lex| lex.set_end(0))]
+            A,
+        }
+
+        let _ = Token::lexer(" a").next();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid Lexer end")]
+    fn past_the_end() {
+        #[derive(Logos, Debug, PartialEq)]
+        enum Token {
+            #[token("a", |lex| lex.set_end(2))]
+            A,
+        }
+
+        let _ = Token::lexer("a").next();
+    }
+}
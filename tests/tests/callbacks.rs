@@ -419,3 +419,189 @@ mod skip_all_callback_types {
         );
     }
 }
+
+mod nested_comments {
+    use super::*;
+    use logos::callbacks::{nested, Unterminated};
+
+    #[derive(Default, Debug, Clone, PartialEq)]
+    enum Error {
+        Unterminated(logos::Span),
+        #[default]
+        Other,
+    }
+
+    impl From<Unterminated> for Error {
+        fn from(err: Unterminated) -> Self {
+            Error::Unterminated(err.span)
+        }
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(error = Error)]
+    #[logos(skip r"[ \t\n\f]+")]
+    #[logos(skip(r"\(\*", logos::callbacks::nested("(*", "*)")))]
+    enum Token {
+        #[token("/*", callback = nested("/*", "*/"))]
+        Comment,
+
+        #[token("{-", callback = nested(b"{-", b"-}"))]
+        HaskellComment,
+
+        #[regex("[a-z]+")]
+        Word,
+    }
+
+    #[test]
+    fn balanced() {
+        assert_lex(
+            "a /* b /* c */ d */ e /**/ (* (* *) *) f {- {- -} {- -} -}",
+            &[
+                (Ok(Token::Word), "a", 0..1),
+                (Ok(Token::Comment), "/* b /* c */ d */", 2..19),
+                (Ok(Token::Word), "e", 20..21),
+                (Ok(Token::Comment), "/**/", 22..26),
+                (Ok(Token::Word), "f", 39..40),
+                (Ok(Token::HaskellComment), "{- {- -} {- -} -}", 41..58),
+            ],
+        );
+    }
+
+    #[test]
+    fn unterminated() {
+        assert_lex(
+            "a /* b /* c */ d",
+            &[
+                (Ok(Token::Word), "a", 0..1),
+                (Err(Error::Unterminated(2..4)), "/* b /* c */ d", 2..16),
+            ],
+        );
+
+        let mut lex = Token::lexer("(* *) (*");
+
+        assert_eq!(lex.next(), Some(Err(Error::Unterminated(6..8))));
+        assert_eq!(lex.slice(), "(*");
+        assert_eq!(lex.next(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "nested delimiters must not be empty")]
+    fn empty_delimiter() {
+        #[derive(Logos, Debug, PartialEq)]
+        #[logos(error = Unterminated)]
+        enum Token {
+            #[token("<", callback = nested("", ">"))]
+            Block,
+        }
+
+        let _ = Token::lexer("< >").next();
+    }
+}
+
+mod fenced_literals {
+    use super::*;
+    use logos::callbacks::{fenced, Fenced, Unterminated};
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(error = Unterminated)]
+    #[logos(skip r"[ \t\n\f]+")]
+    enum Token<'s> {
+        #[regex(r#"r#*""#, callback = fenced("r", "\"", "\""))]
+        RawString(Fenced<&'s str>),
+
+        #[regex(r"<<[a-zA-Z_]+\n", callback = fenced("<<", "\n", "\n"))]
+        Heredoc(Fenced<&'s str>),
+
+        #[regex(r#"br#*""#, |lex| fenced("br", "\"", "\"")(lex).map(|f| f.content.len()))]
+        RawBytesLen(usize),
+    }
+
+    #[test]
+    fn raw_strings() {
+        assert_lex(
+            r####"r"foo" r#"b"a"r"# r##"#"baz"#"## br###"qux"###"####,
+            &[
+                (
+                    Ok(Token::RawString(Fenced {
+                        content: "foo",
+                        fence: "",
+                    })),
+                    r#"r"foo""#,
+                    0..6,
+                ),
+                (
+                    Ok(Token::RawString(Fenced {
+                        content: r#"b"a"r"#,
+                        fence: "#",
+                    })),
+                    r##"r#"b"a"r"#"##,
+                    7..17,
+                ),
+                (
+                    Ok(Token::RawString(Fenced {
+                        content: r##"#"baz"#"##,
+                        fence: "##",
+                    })),
+                    r###"r##"#"baz"#"##"###,
+                    18..32,
+                ),
+                (Ok(Token::RawBytesLen(3)), r####"br###"qux"###"####, 33..46),
+            ],
+        );
+    }
+
+    #[test]
+    fn heredocs() {
+        assert_lex(
+            "<<EOF\nfoo\nEO\nEOF\n<<end\nbar\nend",
+            &[
+                (
+                    Ok(Token::Heredoc(Fenced {
+                        content: "foo\nEO",
+                        fence: "EOF",
+                    })),
+                    "<<EOF\nfoo\nEO\nEOF",
+                    0..16,
+                ),
+                (
+                    Ok(Token::Heredoc(Fenced {
+                        content: "bar",
+                        fence: "end",
+                    })),
+                    "<<end\nbar\nend",
+                    17..30,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn heredoc_closes_on_whole_line() {
+        assert_lex(
+            "<<EOF\nfoo EOF\nEOFS\nEOF\n",
+            &[(
+                Ok(Token::Heredoc(Fenced {
+                    content: "foo EOF\nEOFS",
+                    fence: "EOF",
+                })),
+                "<<EOF\nfoo EOF\nEOFS\nEOF",
+                0..22,
+            )],
+        );
+
+        let mut lex = Token::lexer("<<EOF\nfoo\nEOF bar");
+
+        assert_eq!(lex.next(), Some(Err(Unterminated { span: 0..6 })));
+        assert_eq!(lex.slice(), "<<EOF\nfoo\nEOF bar");
+        assert_eq!(lex.next(), None);
+    }
+
+    #[test]
+    fn unterminated() {
+        let mut lex = Token::lexer("r#\"foo\" bar\"");
+
+        assert_eq!(lex.next(), Some(Err(Unterminated { span: 0..3 })));
+        assert_eq!(lex.slice(), "r#\"foo\" bar\"");
+        assert_eq!(lex.next(), None);
+    }
+}
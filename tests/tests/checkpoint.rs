@@ -0,0 +1,166 @@
+use logos::{Lexer, Logos, SnapshotExtras};
+
+/// Not `Clone`, so the lexer can't be cloned either
+#[derive(Default)]
+struct Extras {
+    depth: usize,
+    idents: Vec<String>,
+}
+
+impl SnapshotExtras for Extras {
+    type Snapshot = (usize, usize);
+
+    fn snapshot(&self) -> Self::Snapshot {
+        (self.depth, self.idents.len())
+    }
+
+    fn restore(&mut self, (depth, idents): Self::Snapshot) {
+        self.depth = depth;
+        self.idents.truncate(idents);
+    }
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(skip r"[ \t\n]+")]
+#[logos(extras = Extras)]
+#[logos(warning = &'static str)]
+enum Token {
+    #[regex("[a-z]+", |lex| lex.extras.idents.push(lex.slice().to_owned()))]
+    Ident,
+
+    #[regex("[A-Z][a-z]*", |lex| lex.warn("uppercase"))]
+    Upper,
+
+    #[token("(", |lex| lex.extras.depth += 1)]
+    LParen,
+
+    #[token(")", |lex| lex.extras.depth -= 1)]
+    RParen,
+
+    #[token("=>")]
+    Arrow,
+}
+
+/// Lambdas `(a b) => x` and parenthesized expressions `(a b)` share a prefix,
+/// try the former and backtrack to the latter.
+fn lambda(lex: &mut Lexer<Token>) -> Option<Vec<String>> {
+    let checkpoint = lex.checkpoint();
+    let mut params = Vec::new();
+
+    if lex.next() != Some(Ok(Token::LParen)) {
+        lex.restore(checkpoint);
+        return None;
+    }
+
+    loop {
+        match lex.next() {
+            Some(Ok(Token::Ident | Token::Upper)) => params.push(lex.slice().to_owned()),
+            Some(Ok(Token::RParen)) if lex.next() == Some(Ok(Token::Arrow)) => {
+                return Some(params);
+            }
+            _ => {
+                lex.restore(checkpoint);
+                return None;
+            }
+        }
+    }
+}
+
+#[test]
+fn backtrack() {
+    let mut lex = Token::lexer("(a B) c");
+
+    assert_eq!(lambda(&mut lex), None);
+    assert_eq!(lex.span(), 0..0);
+    assert_eq!(lex.extras.depth, 0);
+    assert!(lex.extras.idents.is_empty());
+    assert_eq!(lex.warnings().count(), 0);
+
+    assert_eq!(lex.next(), Some(Ok(Token::LParen)));
+    assert_eq!(lex.next(), Some(Ok(Token::Ident)));
+    assert_eq!(lex.extras.depth, 1);
+    assert_eq!(lex.extras.idents, ["a"]);
+}
+
+#[test]
+fn commit() {
+    let mut lex = Token::lexer("(a B) => a");
+
+    assert_eq!(lambda(&mut lex), Some(vec!["a".into(), "B".into()]));
+    assert_eq!(lex.span(), 6..8);
+    assert_eq!(lex.take_warnings(), [("uppercase", 3..4)]);
+    assert_eq!(lex.next(), Some(Ok(Token::Ident)));
+    assert_eq!(lex.extras.idents, ["a", "a"]);
+}
+
+#[test]
+fn restore_twice() {
+    let mut lex = Token::lexer("a b c");
+
+    assert_eq!(lex.next(), Some(Ok(Token::Ident)));
+
+    let checkpoint = lex.checkpoint();
+
+    assert_eq!(checkpoint.span(), 0..1);
+
+    for _ in 0..2 {
+        lex.restore(checkpoint.clone());
+
+        assert_eq!(lex.next(), Some(Ok(Token::Ident)));
+        assert_eq!(lex.slice(), "b");
+        assert_eq!(lex.extras.idents, ["a", "b"]);
+    }
+}
+
+#[test]
+fn restore_after_taking_warnings() {
+    let mut lex = Token::lexer("A b C D");
+
+    assert_eq!(lex.next(), Some(Ok(Token::Upper)));
+
+    let checkpoint = lex.checkpoint();
+
+    assert_eq!(lex.next(), Some(Ok(Token::Ident)));
+    assert_eq!(lex.next(), Some(Ok(Token::Upper)));
+    assert_eq!(
+        lex.take_warnings(),
+        [("uppercase", 0..1), ("uppercase", 4..5)]
+    );
+    assert_eq!(lex.next(), Some(Ok(Token::Upper)));
+
+    // Only the warning recorded since the warnings were taken is discarded
+    lex.restore(checkpoint);
+
+    assert_eq!(lex.warnings().count(), 0);
+    assert_eq!(lex.next(), Some(Ok(Token::Ident)));
+    assert_eq!(lex.next(), Some(Ok(Token::Upper)));
+    assert_eq!(lex.take_warnings(), [("uppercase", 4..5)]);
+}
+
+mod tuple_extras {
+    use logos::Logos;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(extras = (usize, bool))]
+    enum Token {
+        #[token("\n", |lex| lex.extras.0 += 1)]
+        Newline,
+
+        #[token("!", |lex| lex.extras.1 = true)]
+        Bang,
+    }
+
+    #[test]
+    fn restore() {
+        let mut lex = Token::lexer("\n!\n");
+        let checkpoint = lex.checkpoint();
+
+        assert_eq!(lex.by_ref().count(), 3);
+        assert_eq!(lex.extras, (2, true));
+
+        lex.restore(checkpoint);
+
+        assert_eq!(lex.extras, (0, false));
+        assert_eq!(lex.remainder(), "\n!\n");
+    }
+}
@@ -0,0 +1,83 @@
+use logos::Logos;
+use tests::assert_lex;
+
+macro_rules! lexer {
+    ($module:ident, $($attr:tt)*) => {
+        mod $module {
+            use super::*;
+
+            #[derive(Logos, Debug, PartialEq)]
+            #[logos(skip r"[ \t\n]+")]
+            $($attr)*
+            pub enum Token<'s> {
+                #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+                Ident(&'s str),
+
+                #[token("fn")]
+                Fn,
+
+                #[regex("[0-9]+", |lex| lex.slice().parse::<u64>().ok())]
+                Number(u64),
+
+                #[regex(r#""([^"\\]|\\.)*""#)]
+                String(&'s str),
+
+                #[token("(")]
+                Open,
+
+                #[token(")")]
+                Close,
+
+                #[token("//", |lex| { lex.bump_until(b'\n'); })]
+                Comment,
+            }
+
+            #[test]
+            fn lex() {
+                assert_lex(
+                    r#"fn foo(42) "a\"b" ? fnord"#,
+                    &[
+                        (Ok(Token::Fn), "fn", 0..2),
+                        (Ok(Token::Ident("foo")), "foo", 3..6),
+                        (Ok(Token::Open), "(", 6..7),
+                        (Ok(Token::Number(42)), "42", 7..9),
+                        (Ok(Token::Close), ")", 9..10),
+                        (Ok(Token::String(r#""a\"b""#)), r#""a\"b""#, 11..17),
+                        (Err(()), "?", 18..19),
+                        (Ok(Token::Ident("fnord")), "fnord", 20..25),
+                    ],
+                );
+            }
+
+            #[test]
+            fn comment() {
+                assert_lex(
+                    "foo // bar (\n42 // baz\n",
+                    &[
+                        (Ok(Token::Ident("foo")), "foo", 0..3),
+                        (Ok(Token::Comment), "// bar (", 4..12),
+                        (Ok(Token::Number(42)), "42", 13..15),
+                        (Ok(Token::Comment), "// baz", 16..22),
+                    ],
+                );
+            }
+
+            #[test]
+            fn unterminated() {
+                assert_lex(
+                    r#"foo "bar"#,
+                    &[
+                        (Ok(Token::Ident("foo")), "foo", 0..3),
+                        (Err(()), r#""bar"#, 4..8),
+                    ],
+                );
+            }
+        }
+    };
+}
+
+lexer!(tailcall, #[logos(codegen = "tailcall")]);
+lexer!(state_machine, #[logos(codegen = "state_machine")]);
+lexer!(table, #[logos(codegen = "table")]);
+lexer!(forbid_unsafe, #[logos(forbid_unsafe)]);
+lexer!(table_forbid_unsafe, #[logos(codegen = "table", forbid_unsafe)]);
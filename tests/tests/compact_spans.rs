@@ -0,0 +1,30 @@
+use logos::{Logos, SpannedIter};
+use std::ops::Range;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(skip " +")]
+enum Token {
+    #[regex("[a-z]+")]
+    Word,
+}
+
+#[test]
+fn u32_spans() {
+    let mut spanned: SpannedIter<Token, u32> = Token::lexer("foo bar").spanned_as();
+
+    assert_eq!(spanned.next(), Some((Ok(Token::Word), 0..3)));
+    assert_eq!(spanned.next(), Some((Ok(Token::Word), 4..7)));
+    assert_eq!(spanned.slice(), "bar");
+    assert_eq!(spanned.next(), None);
+}
+
+#[test]
+fn spanned_as() {
+    let spans: Vec<Range<u32>> = Token::lexer("a bc  def")
+        .spanned_as()
+        .map(|(_, span)| span)
+        .collect();
+
+    assert_eq!(spans, [0..1, 2..4, 6..9]);
+    assert_eq!(std::mem::size_of_val(&spans[0]), 8);
+}
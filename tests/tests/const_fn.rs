@@ -0,0 +1,106 @@
+use logos::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(const_fn, skip r"[ \t\n]+")]
+enum Token {
+    #[token("SELECT")]
+    Select,
+
+    #[token("FROM")]
+    From,
+
+    #[token("WHERE")]
+    Where,
+
+    #[token(",")]
+    Comma,
+
+    #[token("=")]
+    Equals,
+
+    #[regex("[a-z_]+")]
+    Ident,
+
+    #[regex(r"\$[0-9]+")]
+    Param,
+}
+
+/// Counts the tokens of a query, failing to compile if it doesn't lex.
+const fn count_tokens(query: &str) -> usize {
+    let mut count = 0;
+    let mut offset = 0;
+    while let Some((token, span)) = Token::lex_const(query, offset) {
+        if token.is_err() {
+            panic!("Invalid query");
+        }
+        count += 1;
+        offset = span.end;
+    }
+    count
+}
+
+const QUERY: &str = "SELECT id, name FROM users WHERE id = $1";
+const QUERY_TOKENS: usize = count_tokens(QUERY);
+
+#[test]
+fn in_const_context() {
+    assert_eq!(QUERY_TOKENS, 10);
+}
+
+#[test]
+fn same_as_lexer() {
+    let mut offset = 0;
+    let mut lex = Token::lexer(QUERY);
+    while let Some((token, span)) = Token::lex_const(QUERY, offset) {
+        assert_eq!(lex.next(), Some(token));
+        assert_eq!(lex.slice(), &QUERY[span.clone()]);
+        offset = span.end;
+    }
+    assert_eq!(lex.next(), None);
+}
+
+#[test]
+fn errors() {
+    const SOURCE: &str = "SELECT ż! name";
+
+    assert_eq!(Token::lex_const(SOURCE, 0), Some((Ok(Token::Select), 0..6)));
+    assert_eq!(Token::lex_const(SOURCE, 6), Some((Err(()), 7..9)));
+    assert_eq!(Token::lex_const(SOURCE, 9), Some((Err(()), 9..10)));
+    assert_eq!(
+        Token::lex_const(SOURCE, 10),
+        Some((Ok(Token::Ident), 11..15))
+    );
+    assert_eq!(Token::lex_const(SOURCE, 15), None);
+}
+
+#[test]
+fn end_of_input() {
+    assert_eq!(Token::lex_const("", 0), None);
+    assert_eq!(Token::lex_const("   ", 0), None);
+    assert_eq!(Token::lex_const("FROM", 4), None);
+    assert_eq!(Token::lex_const("FROM", 10), None);
+}
+
+mod bytes {
+    use super::*;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    #[logos(const_fn, utf8 = false)]
+    enum Token {
+        #[token(b"\x00\xff")]
+        Marker,
+
+        #[regex(b"[\x01-\x7f]+")]
+        Ascii,
+    }
+
+    const SOURCE: &[u8] = b"ab\x00\xff\x80";
+
+    #[test]
+    fn lex() {
+        assert_eq!(Token::lex_const(SOURCE, 0), Some((Ok(Token::Ascii), 0..2)));
+        assert_eq!(Token::lex_const(SOURCE, 2), Some((Ok(Token::Marker), 2..4)));
+        assert_eq!(Token::lex_const(SOURCE, 4), Some((Err(()), 4..5)));
+        assert_eq!(Token::lex_const(SOURCE, 5), None);
+    }
+}
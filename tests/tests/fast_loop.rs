@@ -0,0 +1,95 @@
+use logos::Logos;
+use tests::assert_lex;
+
+mod strings {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(skip r"[ \n]+")]
+    enum Token<'s> {
+        #[regex(r#""([^"\\\n]|\\.)*""#)]
+        String(&'s str),
+
+        #[regex(r"//[^\n]*", allow_greedy = true)]
+        Comment(&'s str),
+
+        #[regex("[a-z]+")]
+        Word(&'s str),
+    }
+
+    #[test]
+    fn exit_at_every_position() {
+        for len in 0..40 {
+            let body = "x".repeat(len);
+            let source = format!(r#""{body}" "{body}\"" "{body}"#);
+
+            let mut lex = Token::lexer(&source);
+
+            assert_eq!(lex.next(), Some(Ok(Token::String(&source[..len + 2]))));
+            assert_eq!(
+                lex.next(),
+                Some(Ok(Token::String(&source[len + 3..2 * len + 7])))
+            );
+            assert_eq!(lex.next(), Some(Err(())));
+            assert_eq!(lex.slice(), &source[2 * len + 8..]);
+            assert_eq!(lex.next(), None);
+        }
+    }
+
+    #[test]
+    fn non_ascii() {
+        assert_lex(
+            r#""zażółć gęślą jaźń, long enough to read whole words" foo"#,
+            &[
+                (
+                    Ok(Token::String(
+                        r#""zażółć gęślą jaźń, long enough to read whole words""#,
+                    )),
+                    r#""zażółć gęślą jaźń, long enough to read whole words""#,
+                    0..61,
+                ),
+                (Ok(Token::Word("foo")), "foo", 62..65),
+            ],
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let comment = "// a comment that spans more than 8 bytes, with ünïcödé";
+        let source = format!("{comment}\nfoo\n//\n// end");
+
+        let tokens: Vec<_> = Token::lexer(&source).collect();
+
+        assert_eq!(
+            tokens,
+            [
+                Ok(Token::Comment(comment)),
+                Ok(Token::Word("foo")),
+                Ok(Token::Comment("//")),
+                Ok(Token::Comment("// end")),
+            ]
+        );
+    }
+}
+
+mod bytes {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(utf8 = false)]
+    enum Token<'s> {
+        #[regex(b"<[^>\x00]*>")]
+        Tag(&'s [u8]),
+    }
+
+    #[test]
+    fn binary_body() {
+        let source = b"<\xff\xfe long binary tag body \x80\x81><\x00>";
+
+        let mut lex = Token::lexer(&source[..]);
+
+        assert_eq!(lex.next(), Some(Ok(Token::Tag(&source[..28]))));
+        assert_eq!(lex.next(), Some(Err(())));
+        assert_eq!(lex.span(), 28..29);
+    }
+}
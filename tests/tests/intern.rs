@@ -0,0 +1,165 @@
+use logos::{Interner, Lexer, Logos};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Symbol(u32);
+
+#[derive(Default)]
+struct Table {
+    symbols: HashMap<String, Symbol>,
+    names: Vec<String>,
+}
+
+impl Table {
+    fn resolve(&self, symbol: Symbol) -> &str {
+        &self.names[symbol.0 as usize]
+    }
+}
+
+impl Interner<&str> for Table {
+    type Symbol = Symbol;
+
+    fn intern(&mut self, slice: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(slice) {
+            return symbol;
+        }
+        let symbol = Symbol(self.names.len() as u32);
+
+        self.symbols.insert(slice.to_owned(), symbol);
+        self.names.push(slice.to_owned());
+        symbol
+    }
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(skip r"[ \t\n]+")]
+#[logos(extras = Table)]
+enum Token {
+    #[regex("[a-z_][a-z0-9_]*", intern)]
+    Ident(Symbol),
+
+    #[token("self", intern, priority = 5)]
+    SelfKw(Symbol),
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u64),
+}
+
+#[test]
+fn symbols() {
+    let mut lex = Token::lexer("foo bar self 42 foo self");
+
+    assert_eq!(lex.next(), Some(Ok(Token::Ident(Symbol(0)))));
+    assert_eq!(lex.next(), Some(Ok(Token::Ident(Symbol(1)))));
+    assert_eq!(lex.next(), Some(Ok(Token::SelfKw(Symbol(2)))));
+    assert_eq!(lex.next(), Some(Ok(Token::Number(42))));
+    assert_eq!(lex.next(), Some(Ok(Token::Ident(Symbol(0)))));
+    assert_eq!(lex.next(), Some(Ok(Token::SelfKw(Symbol(2)))));
+    assert_eq!(lex.next(), None);
+
+    assert_eq!(lex.extras.resolve(Symbol(1)), "bar");
+    assert_eq!(lex.extras.names.len(), 3);
+}
+
+mod external_table {
+    use super::{Symbol, Table};
+    use logos::Logos;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(skip " +")]
+    #[logos(extras = &'e mut Table)]
+    enum Token {
+        #[regex("[a-z]+", intern)]
+        Ident(Symbol),
+    }
+
+    #[test]
+    fn shared_between_lexers() {
+        let mut table = Table::default();
+
+        let first: Vec<_> = Token::lexer_with_extras("a b", &mut table).collect();
+
+        assert_eq!(
+            first,
+            [Ok(Token::Ident(Symbol(0))), Ok(Token::Ident(Symbol(1)))]
+        );
+
+        let second: Vec<_> = Token::lexer_with_extras("b c", &mut table).collect();
+
+        assert_eq!(
+            second,
+            [Ok(Token::Ident(Symbol(1))), Ok(Token::Ident(Symbol(2)))]
+        );
+        assert_eq!(table.resolve(Symbol(2)), "c");
+    }
+}
+
+mod bytes {
+    use logos::{Interner, Logos};
+
+    /// Zero-copy interner keeping slices of the source
+    #[derive(Default)]
+    struct Slices<'s>(Vec<&'s [u8]>);
+
+    impl<'s> Interner<&'s [u8]> for Slices<'s> {
+        type Symbol = Option<usize>;
+
+        fn intern(&mut self, slice: &'s [u8]) -> Option<usize> {
+            // Reject reserved words, the lexer produces an error instead
+            if slice == b"\xFF\xFF" {
+                return None;
+            }
+            match self.0.iter().position(|&s| s == slice) {
+                Some(index) => Some(index),
+                None => {
+                    self.0.push(slice);
+                    Some(self.0.len() - 1)
+                }
+            }
+        }
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(extras = Slices<'s>)]
+    #[logos(utf8 = false)]
+    enum Token {
+        #[regex(b"[\x80-\xFF]+", intern)]
+        High(usize),
+
+        #[token(b" ")]
+        Space,
+    }
+
+    #[test]
+    fn filter() {
+        let mut lex = Token::lexer(b"\x80 \xFF\xFF \x80");
+
+        assert_eq!(lex.next(), Some(Ok(Token::High(0))));
+        assert_eq!(lex.next(), Some(Ok(Token::Space)));
+        assert_eq!(lex.next(), Some(Err(())));
+        assert_eq!(lex.next(), Some(Ok(Token::Space)));
+        assert_eq!(lex.next(), Some(Ok(Token::High(0))));
+        assert_eq!(lex.extras.0, [b"\x80"]);
+    }
+}
+
+mod callback_named_intern {
+    use super::*;
+
+    fn intern(lex: &mut Lexer<Token>) -> usize {
+        lex.slice().len()
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    enum Token {
+        #[regex("[a-z]+", callback = intern)]
+        Word(usize),
+    }
+
+    #[test]
+    fn user_callback() {
+        let mut lex = Token::lexer("abc");
+
+        assert_eq!(lex.next(), Some(Ok(Token::Word(3))));
+    }
+}
@@ -0,0 +1,82 @@
+use logos::{Lexer, Logos};
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(skip r" +")]
+enum Token {
+    #[token("continue")]
+    Continue,
+
+    #[token("const")]
+    Const,
+
+    #[token("abcdefghijklmnopqrstuvwxyz")]
+    Alphabet,
+
+    #[regex("[0-9]+")]
+    Number,
+}
+
+#[test]
+fn keywords() {
+    assert_lex(
+        "continue const 42 abcdefghijklmnopqrstuvwxyz",
+        &[
+            (Ok(Token::Continue), "continue", 0..8),
+            (Ok(Token::Const), "const", 9..14),
+            (Ok(Token::Number), "42", 15..17),
+            (Ok(Token::Alphabet), "abcdefghijklmnopqrstuvwxyz", 18..44),
+        ],
+    );
+}
+
+#[test]
+fn keywords_without_whitespace() {
+    assert_lex(
+        "continueconst1continue",
+        &[
+            (Ok(Token::Continue), "continue", 0..8),
+            (Ok(Token::Const), "const", 8..13),
+            (Ok(Token::Number), "1", 13..14),
+            (Ok(Token::Continue), "continue", 14..22),
+        ],
+    );
+}
+
+#[test]
+fn mismatch_at_every_position() {
+    let keyword = "abcdefghijklmnopqrstuvwxyz";
+    for len in 1..keyword.len() {
+        let source = format!("{}! const", &keyword[..len]);
+        let mut lex = Token::lexer(&source);
+
+        assert_eq!(lex.next(), Some(Err(())));
+        assert_eq!(lex.slice(), &source[..len]);
+        assert_eq!(lex.next(), Some(Err(())));
+        assert_eq!(lex.slice(), "!");
+        assert_eq!(lex.next(), Some(Ok(Token::Const)));
+        assert_eq!(lex.next(), None);
+    }
+}
+
+#[test]
+fn end_of_input_at_every_position() {
+    let keyword = "abcdefghijklmnopqrstuvwxyz";
+    for len in 1..keyword.len() {
+        let source = &keyword[..len];
+        let mut lex = Token::lexer(source);
+
+        assert_eq!(lex.next(), Some(Err(())));
+        assert_eq!(lex.slice(), source);
+        assert_eq!(lex.next(), None);
+    }
+}
+
+#[test]
+fn partial_keyword() {
+    let mut lex = Lexer::<Token>::new_partial("const contin");
+
+    assert_eq!(lex.next(), Some(Ok(Token::Const)));
+    assert_eq!(lex.next(), None);
+    assert_eq!(lex.remainder(), "contin");
+}
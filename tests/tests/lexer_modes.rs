@@ -118,3 +118,69 @@ fn iterating_modes() {
     ];
     assert_eq!(results, expect);
 }
+
+mod sublex {
+    use super::*;
+    use logos::Span;
+
+    #[derive(Debug, PartialEq, Eq, Logos)]
+    #[logos(extras = usize)]
+    enum Outer {
+        #[token("\"", |lex| lex.sublex(|count| count, string))]
+        String(Vec<(Inner, Span)>),
+
+        #[regex(r"\p{White_Space}")]
+        WhiteSpace,
+    }
+
+    fn string(lex: &mut Lexer<Inner>) -> Option<Vec<(Inner, Span)>> {
+        let mut tokens = Vec::new();
+
+        while let Some(token) = lex.next() {
+            *lex.extras += 1;
+
+            match token.ok()? {
+                Inner::EndString => return Some(tokens),
+                token => tokens.push((token, lex.span())),
+            }
+        }
+        None
+    }
+
+    #[derive(Debug, PartialEq, Eq, Logos)]
+    #[logos(extras = &'e mut usize)]
+    enum Inner {
+        #[regex(r#"[^\\"]+"#)]
+        Text,
+
+        #[token("\\n")]
+        EscapedNewline,
+
+        #[token("\"")]
+        EndString,
+    }
+
+    #[test]
+    fn absolute_spans() {
+        let mut lex = Outer::lexer(r#" "foo\nbar" "baz"#);
+
+        assert_eq!(lex.next(), Some(Ok(Outer::WhiteSpace)));
+        assert_eq!(
+            lex.next(),
+            Some(Ok(Outer::String(vec![
+                (Inner::Text, 2..5),
+                (Inner::EscapedNewline, 5..7),
+                (Inner::Text, 7..10),
+            ])))
+        );
+        assert_eq!(lex.span(), 1..11);
+        assert_eq!(lex.extras, 4);
+        assert_eq!(lex.next(), Some(Ok(Outer::WhiteSpace)));
+
+        // Unterminated string, the inner lexer consumed everything
+        assert_eq!(lex.next(), Some(Err(())));
+        assert_eq!(lex.slice(), "\"baz");
+        assert_eq!(lex.extras, 5);
+        assert_eq!(lex.next(), None);
+    }
+}
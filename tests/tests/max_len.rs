@@ -0,0 +1,148 @@
+use logos::{Logos, TokenTooLong};
+use tests::assert_lex;
+
+#[derive(Default, Debug, Clone, PartialEq)]
+enum LexingError {
+    TooLong(usize),
+    #[default]
+    Other,
+}
+
+impl From<TokenTooLong> for LexingError {
+    fn from(err: TokenTooLong) -> Self {
+        LexingError::TooLong(err.limit)
+    }
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(skip " +", error = LexingError)]
+#[logos(max_token_len = 4)]
+enum Token {
+    #[regex("[a-z]+")]
+    Word,
+
+    #[regex(r#""[^"]*""#, max_len = 8)]
+    String,
+
+    #[regex("[0-9]+", max_len = 3)]
+    Small,
+
+    #[regex("[0-9]+x", max_len = 64)]
+    Tagged,
+}
+
+#[test]
+fn within_limits() {
+    assert_lex(
+        r#"abcd "a b c" 123 12345x"#,
+        &[
+            (Ok(Token::Word), "abcd", 0..4),
+            (Ok(Token::String), r#""a b c""#, 5..12),
+            (Ok(Token::Small), "123", 13..16),
+            (Ok(Token::Tagged), "12345x", 17..23),
+        ],
+    );
+}
+
+#[test]
+fn max_token_len() {
+    assert_lex(
+        "abcdefgh ab",
+        &[
+            (Err(LexingError::TooLong(4)), "abcde", 0..5),
+            (Ok(Token::Word), "fgh", 5..8),
+            (Ok(Token::Word), "ab", 9..11),
+        ],
+    );
+}
+
+#[test]
+fn pattern_max_len() {
+    assert_lex(
+        r#""abcdefghijkl""#,
+        &[
+            (Err(LexingError::TooLong(8)), r#""abcdefgh"#, 0..9),
+            (Ok(Token::Word), "ijkl", 9..13),
+            (Err(LexingError::Other), r#"""#, 13..14),
+        ],
+    );
+}
+
+#[test]
+fn shared_states() {
+    // The states reading digits can still match `Tagged`, so the limit of
+    // `Small` is only checked once the token has been matched
+    assert_lex(
+        "12345 1",
+        &[
+            (Err(LexingError::TooLong(3)), "12345", 0..5),
+            (Ok(Token::Small), "1", 6..7),
+        ],
+    );
+}
+
+#[test]
+fn skip() {
+    assert_lex(
+        "a      b",
+        &[
+            (Ok(Token::Word), "a", 0..1),
+            (Err(LexingError::TooLong(4)), "     ", 1..6),
+            (Ok(Token::Word), "b", 7..8),
+        ],
+    );
+}
+
+#[test]
+fn error_span_len() {
+    // Too long tokens stop at the first byte past the limit
+    for len in 1..20 {
+        let word = "a".repeat(len);
+        let string = format!(r#""{word}""#);
+        let spaces = format!("a{}b", " ".repeat(len));
+        for source in [&word, &string, &spaces] {
+            for (token, span) in Token::lexer(source).spanned() {
+                if let Err(LexingError::TooLong(limit)) = token {
+                    assert!(span.len() <= limit + 1, "{source:?}: {span:?}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn char_boundary() {
+    assert_lex::<Token>(
+        r#""ééééé""#,
+        &[
+            (Err(LexingError::TooLong(8)), r#""éééé"#, 0..9),
+            (Err(LexingError::Other), "é", 9..11),
+            (Err(LexingError::Other), r#"""#, 11..12),
+        ],
+    );
+}
+
+mod const_fn {
+    use super::*;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    #[logos(const_fn, max_token_len = 4)]
+    enum Token {
+        #[regex("[a-z]+")]
+        Word,
+
+        #[regex("[0-9]+", max_len = 2)]
+        Small,
+
+        #[regex("[0-9]+x", max_len = 8)]
+        Tagged,
+    }
+
+    #[test]
+    fn lex_const() {
+        assert_eq!(Token::lex_const("abcd", 0), Some((Ok(Token::Word), 0..4)));
+        assert_eq!(Token::lex_const("abcdefg", 0), Some((Err(()), 0..5)));
+        assert_eq!(Token::lex_const("123", 0), Some((Err(()), 0..3)));
+        assert_eq!(Token::lex_const("123x", 0), Some((Ok(Token::Tagged), 0..4)));
+    }
+}
@@ -0,0 +1,122 @@
+use logos::{Lexer, Logos, TokenEvent};
+
+#[derive(Default, Debug, PartialEq)]
+struct Stats {
+    line: usize,
+    tokens: usize,
+    errors: usize,
+    skips: usize,
+    comments: usize,
+}
+
+fn count<'s>(lex: &mut Lexer<'s, Token<'s>>, event: TokenEvent<'_, 's, Token<'s>>) {
+    match event {
+        TokenEvent::Token(_) => lex.extras.tokens += 1,
+        TokenEvent::Error(_) => lex.extras.errors += 1,
+        TokenEvent::Skip(index) => {
+            lex.extras.skips += 1;
+            lex.extras.line += lex.slice().matches('\n').count();
+
+            // Patterns are numbered with skip patterns first, `Comment` is the third one
+            if index == 2 {
+                lex.extras.comments += 1;
+            }
+        }
+    }
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(skip r"[ \n]+")]
+#[logos(extras = Stats)]
+#[logos(on_token = count)]
+enum Token<'s> {
+    #[regex("[a-z]+")]
+    Word(&'s str),
+
+    // Skipped by a callback rather than a skip pattern
+    #[regex("#[^\n]*", logos::skip, allow_greedy = true)]
+    Comment,
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u8),
+}
+
+#[test]
+fn all_events() {
+    let mut lex = Token::lexer("foo # bar\n 300 baz\n\n!");
+
+    assert_eq!(lex.next(), Some(Ok(Token::Word("foo"))));
+    // 300 doesn't fit in u8
+    assert_eq!(lex.next(), Some(Err(())));
+    assert_eq!(lex.next(), Some(Ok(Token::Word("baz"))));
+    // Unknown input
+    assert_eq!(lex.next(), Some(Err(())));
+    assert_eq!(lex.next(), None);
+
+    assert_eq!(
+        lex.extras,
+        Stats {
+            line: 3,
+            tokens: 2,
+            errors: 2,
+            skips: 5,
+            comments: 1,
+        }
+    );
+}
+
+mod generic_hook {
+    use logos::{Lexer, Logos, Span, TokenEvent};
+
+    /// Hook shared by several token types
+    fn brackets<'s, T: Logos<'s, Extras = Vec<Span>> + Bracket>(
+        lex: &mut Lexer<'s, T>,
+        event: TokenEvent<'_, 's, T>,
+    ) {
+        if let TokenEvent::Token(token) = event {
+            match token.bracket() {
+                Some(true) => lex.extras.push(lex.span()),
+                Some(false) => {
+                    lex.extras.pop();
+                }
+                None => (),
+            }
+        }
+    }
+
+    trait Bracket {
+        fn bracket(&self) -> Option<bool>;
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(extras = Vec<Span>)]
+    #[logos(on_token = brackets)]
+    enum Token {
+        #[token("(")]
+        Open,
+
+        #[token(")")]
+        Close,
+
+        #[token("x")]
+        X,
+    }
+
+    impl Bracket for Token {
+        fn bracket(&self) -> Option<bool> {
+            match self {
+                Token::Open => Some(true),
+                Token::Close => Some(false),
+                Token::X => None,
+            }
+        }
+    }
+
+    #[test]
+    fn unclosed_brackets() {
+        let mut lex = Token::lexer("(x(()x(");
+
+        assert_eq!(lex.by_ref().count(), 7);
+        assert_eq!(lex.extras, [0..1, 2..3, 6..7]);
+    }
+}
@@ -0,0 +1,175 @@
+use logos::{Logos, OwnedLexer};
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(skip r"[ \t\n\f]+")]
+#[logos(extras = usize)]
+enum Token {
+    #[regex("[a-z]+", |lex| lex.extras += 1)]
+    Word,
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u32),
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(utf8 = false)]
+enum Byte {
+    #[token(b"\xFF")]
+    Marker,
+
+    #[regex(b"[\x00-\x7F]+")]
+    Ascii,
+}
+
+struct State {
+    lexer: OwnedLexer<Token, String>,
+}
+
+#[test]
+fn string() {
+    let mut state = State {
+        lexer: OwnedLexer::new(String::from("foo 42 bar")),
+    };
+
+    assert_eq!(state.lexer.next(), Some(Ok(Token::Word)));
+    assert_eq!(state.lexer.slice(), "foo");
+    assert_eq!(state.lexer.remainder(), " 42 bar");
+    assert_eq!(state.lexer.next(), Some(Ok(Token::Number(42))));
+    assert_eq!(state.lexer.span(), 4..6);
+    assert_eq!(state.lexer.next(), Some(Ok(Token::Word)));
+    assert_eq!(state.lexer.next(), None);
+    assert_eq!(*state.lexer.extras(), 2);
+    assert_eq!(state.lexer.into_source(), "foo 42 bar");
+}
+
+#[test]
+fn shared_sources() {
+    let source: Arc<str> = Arc::from("a 1 b");
+    let arc = OwnedLexer::<Token, _>::with_extras(source.clone(), 10);
+
+    assert_eq!(
+        arc.spanned().collect::<Vec<_>>(),
+        &[
+            (Ok(Token::Word), 0..1),
+            (Ok(Token::Number(1)), 2..3),
+            (Ok(Token::Word), 4..5),
+        ]
+    );
+
+    let boxed: Box<str> = "c 2".into();
+    let mut lex = OwnedLexer::<Token, _>::new(boxed).spanned();
+
+    assert_eq!(lex.next(), Some((Ok(Token::Word), 0..1)));
+    *lex.extras_mut() += 5;
+    assert_eq!(lex.next(), Some((Ok(Token::Number(2)), 2..3)));
+    assert_eq!(*lex.extras(), 6);
+
+    let rc: Rc<str> = Rc::from("3");
+    let lex = OwnedLexer::<Token, _>::new(rc);
+
+    assert_eq!(lex.collect::<Vec<_>>(), &[Ok(Token::Number(3))]);
+}
+
+#[test]
+fn bytes() {
+    let mut lex = OwnedLexer::<Byte, Vec<u8>>::new(b"ab\xFFcd".to_vec());
+
+    assert_eq!(lex.next(), Some(Ok(Byte::Ascii)));
+    assert_eq!(lex.slice(), b"ab");
+    assert_eq!(lex.next(), Some(Ok(Byte::Marker)));
+    assert_eq!(lex.next(), Some(Ok(Byte::Ascii)));
+    assert_eq!(lex.slice(), b"cd");
+    assert_eq!(lex.next(), None);
+}
+
+/// Dereferences to a shorter string every time
+struct Shrinking(std::cell::Cell<&'static str>);
+
+impl std::ops::Deref for Shrinking {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        let source = self.0.get();
+        self.0.set(&source[..source.len().saturating_sub(2)]);
+        source
+    }
+}
+
+#[test]
+#[should_panic(expected = "Source of OwnedLexer changed while lexing")]
+fn unstable_deref() {
+    let mut lex = OwnedLexer::<Token, _>::new(Shrinking(std::cell::Cell::new("foobar")));
+
+    assert_eq!(lex.next(), Some(Ok(Token::Word)));
+    lex.slice();
+}
+
+mod owned_tokens {
+    use logos::Logos;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    #[logos(owned(TokenOwned, derive(Debug, Clone, PartialEq)))]
+    #[logos(skip r"[ \t\n\f]+")]
+    enum Token<'a> {
+        #[regex("[a-z]+")]
+        Ident(&'a str),
+
+        #[regex(r#""[^"]*""#, |lex| { (&lex.slice()[1..lex.slice().len() - 1], lex.span().start) })]
+        String((&'a str, usize)),
+
+        #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+        Number(u32),
+
+        #[token("=")]
+        Assign,
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(owned = BytesOwned)]
+    #[logos(utf8 = false)]
+    enum Bytes<'s> {
+        #[regex(b"[\x80-\xFF]+")]
+        High(&'s [u8]),
+    }
+
+    #[test]
+    fn into_owned() {
+        let tokens: Vec<TokenOwned> = Token::lexer(r#"foo = "bar" 42"#)
+            .map(|token| token.unwrap().into_owned())
+            .collect();
+
+        assert_eq!(
+            tokens,
+            [
+                TokenOwned::Ident("foo".into()),
+                TokenOwned::Assign,
+                TokenOwned::String(("bar".into(), 6)),
+                TokenOwned::Number(42),
+            ]
+        );
+    }
+
+    #[test]
+    fn outlives_source() {
+        let owned = {
+            let source = String::from("foo");
+            let token = Token::lexer(&source).next().unwrap().unwrap();
+
+            assert_eq!(token.to_owned_token(), TokenOwned::Ident("foo".into()));
+            TokenOwned::from(token)
+        };
+
+        assert_eq!(owned, TokenOwned::Ident("foo".into()));
+    }
+
+    #[test]
+    fn bytes() {
+        let mut lex = Bytes::lexer(b"\x80\xFF");
+
+        match lex.next().unwrap().unwrap().into_owned() {
+            BytesOwned::High(bytes) => assert_eq!(bytes, b"\x80\xFF"),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use logos::source::Padded;
+use logos::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(padded, skip r"[ \n]+")]
+enum Token {
+    #[token("let")]
+    Let,
+
+    #[token("=")]
+    Equals,
+
+    #[token(";")]
+    Semicolon,
+
+    #[regex("[a-z]+")]
+    Ident,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    #[regex(r#""[^"]*""#)]
+    String,
+}
+
+#[test]
+fn lex() {
+    let source = Padded::<str>::new("let x = 42;\0").unwrap();
+    let mut lex = Token::lexer(&source);
+
+    assert_eq!(lex.next(), Some(Ok(Token::Let)));
+    assert_eq!(lex.next(), Some(Ok(Token::Ident)));
+    assert_eq!(lex.slice(), "x");
+    assert_eq!(lex.next(), Some(Ok(Token::Equals)));
+    assert_eq!(lex.next(), Some(Ok(Token::Number)));
+    assert_eq!(lex.slice(), "42");
+    assert_eq!(lex.next(), Some(Ok(Token::Semicolon)));
+    assert_eq!(lex.next(), None);
+    assert_eq!(lex.remainder(), "");
+}
+
+#[test]
+fn token_at_end_of_input() {
+    let source = Padded::<str>::new("let\0").unwrap();
+    let mut lex = Token::lexer(&source);
+
+    assert_eq!(lex.next(), Some(Ok(Token::Let)));
+    assert_eq!(lex.next(), None);
+}
+
+#[test]
+fn nul_bytes_in_source() {
+    let source = Padded::<str>::new("\"a\0b\" \0 1\0").unwrap();
+    let mut lex = Token::lexer(&source);
+
+    assert_eq!(lex.next(), Some(Ok(Token::String)));
+    assert_eq!(lex.slice(), "\"a\0b\"");
+    assert_eq!(lex.next(), Some(Err(())));
+    assert_eq!(lex.slice(), "\0");
+    assert_eq!(lex.next(), Some(Ok(Token::Number)));
+    assert_eq!(lex.next(), None);
+}
+
+#[test]
+fn unterminated_at_end_of_input() {
+    let source = Padded::<str>::new("\"abc\0").unwrap();
+    let mut lex = Token::lexer(&source);
+
+    assert_eq!(lex.next(), Some(Err(())));
+    assert_eq!(lex.slice(), "\"abc");
+    assert_eq!(lex.next(), None);
+}
+
+#[test]
+fn empty() {
+    let source = Padded::<str>::new("\0").unwrap();
+
+    assert_eq!(Token::lexer(&source).next(), None);
+}
+
+#[test]
+fn missing_sentinel() {
+    assert!(Padded::<str>::new("let").is_none());
+    assert!(Padded::<str>::new("").is_none());
+}
+
+mod bytes {
+    use super::*;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    #[logos(padded, utf8 = false)]
+    enum Token {
+        #[regex(b"\x00+")]
+        Zeros,
+
+        #[regex(b"[\x01-\xff]+")]
+        Other,
+    }
+
+    #[test]
+    fn zeros_before_sentinel() {
+        let source = Padded::<[u8]>::new(b"\x01\x02\x00\x00\x00").unwrap();
+        let mut lex = Token::lexer(&source);
+
+        assert_eq!(lex.next(), Some(Ok(Token::Other)));
+        assert_eq!(lex.slice(), b"\x01\x02");
+        assert_eq!(lex.next(), Some(Ok(Token::Zeros)));
+        assert_eq!(lex.slice(), b"\x00\x00");
+        assert_eq!(lex.next(), None);
+    }
+}
+
+mod forbid_unsafe {
+    use super::*;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    #[logos(padded, forbid_unsafe, skip r" +")]
+    enum Token {
+        #[regex("[a-z]+")]
+        Word,
+
+        #[regex("[0-9]+")]
+        Number,
+    }
+
+    #[test]
+    fn lex() {
+        let source = Padded::<str>::new("abc 42\0").unwrap();
+        let mut lex = Token::lexer(&source);
+
+        assert_eq!(lex.next(), Some(Ok(Token::Word)));
+        assert_eq!(lex.slice(), "abc");
+        assert_eq!(lex.next(), Some(Ok(Token::Number)));
+        assert_eq!(lex.slice(), "42");
+        assert_eq!(lex.next(), None);
+    }
+}
@@ -0,0 +1,71 @@
+use logos::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(skip r"[ \t\n]")]
+#[logos(profile_corpus = "tests/data/sample.json")]
+enum Token {
+    #[token("{")]
+    BraceOpen,
+
+    #[token("}")]
+    BraceClose,
+
+    #[token("[")]
+    BracketOpen,
+
+    #[token("]")]
+    BracketClose,
+
+    #[token(":")]
+    Colon,
+
+    #[token(",")]
+    Comma,
+
+    #[token("true")]
+    #[token("false")]
+    Bool,
+
+    #[token("null")]
+    Null,
+
+    #[regex(r"-?(?:0|[1-9]\d*)(?:\.\d+)?(?:[eE][+-]?\d+)?")]
+    Number,
+
+    #[regex(r#""([^"\\]|\\["\\/bfnrtu])*""#)]
+    String,
+}
+
+#[test]
+fn sample() {
+    let source = include_str!("data/sample.json");
+    let tokens = Token::lexer(source).collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert_eq!(tokens.len(), 49);
+    assert_eq!(
+        &tokens[..4],
+        &[Token::BraceOpen, Token::String, Token::Colon, Token::String]
+    );
+}
+
+#[test]
+fn unlike_the_sample() {
+    assert_lex(
+        "[[[-1.5e3,\"\\n\"]],{}]",
+        &[
+            (Ok(Token::BracketOpen), "[", 0..1),
+            (Ok(Token::BracketOpen), "[", 1..2),
+            (Ok(Token::BracketOpen), "[", 2..3),
+            (Ok(Token::Number), "-1.5e3", 3..9),
+            (Ok(Token::Comma), ",", 9..10),
+            (Ok(Token::String), "\"\\n\"", 10..14),
+            (Ok(Token::BracketClose), "]", 14..15),
+            (Ok(Token::BracketClose), "]", 15..16),
+            (Ok(Token::Comma), ",", 16..17),
+            (Ok(Token::BraceOpen), "{", 17..18),
+            (Ok(Token::BraceClose), "}", 18..19),
+            (Ok(Token::BracketClose), "]", 19..20),
+        ],
+    );
+}
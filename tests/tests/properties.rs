@@ -40,3 +40,34 @@ fn cyrillic() {
         ],
     )
 }
+
+mod xid {
+    use super::*;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    #[logos(skip r"[ \t\n\f]+")]
+    enum Token {
+        #[regex(r"[\p{XID_Start}_]\p{XID_Continue}*")]
+        Ident,
+
+        #[regex(r"@[\p{XID_Start}_]\p{XID_Continue}*")]
+        Attribute,
+
+        #[regex(r"'\p{XID_Continue}+")]
+        Lifetime,
+    }
+
+    #[test]
+    fn identifiers() {
+        assert_lex(
+            "zażółć @gęślą 'jaźń _λόγος2 Свидания",
+            &[
+                (Ok(Token::Ident), "zażółć", 0..10),
+                (Ok(Token::Attribute), "@gęślą", 11..20),
+                (Ok(Token::Lifetime), "'jaźń", 21..28),
+                (Ok(Token::Ident), "_λόγος2", 29..41),
+                (Ok(Token::Ident), "Свидания", 42..58),
+            ],
+        )
+    }
+}
@@ -0,0 +1,131 @@
+use logos::{Lexer, Logos, TokenEvent};
+
+fn integer(lex: &mut Lexer<Token>) -> Option<u64> {
+    let radix = match lex.rule()?.index() {
+        2 => 10,
+        3 => 16,
+        4 => 8,
+        _ => unreachable!(),
+    };
+    let digits = lex
+        .slice()
+        .trim_start_matches("0x")
+        .trim_start_matches("0o");
+
+    u64::from_str_radix(digits, radix).ok()
+}
+
+fn comments(lex: &mut Lexer<Token>, event: TokenEvent<Token>) {
+    if let TokenEvent::Skip(_) = event {
+        if lex.rule().map(|rule| rule.pattern()) == Some(r"//[^\n]*") {
+            lex.extras += 1;
+        }
+    }
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(skip r"[ \n]+")]
+#[logos(skip(r"//[^\n]*", allow_greedy = true))]
+#[logos(extras = usize)]
+#[logos(on_token = comments)]
+enum Token {
+    #[regex("[0-9]+", integer)]
+    #[regex("0x[0-9a-f]+", integer)]
+    #[token("0o7", integer)]
+    Integer(u64),
+
+    #[token("+")]
+    Plus,
+}
+
+#[test]
+fn shared_callback() {
+    let mut lex = Token::lexer("10 + 0x10 // comment\n+ 0o7 // another");
+
+    assert_eq!(lex.rule(), None);
+    assert_eq!(lex.next(), Some(Ok(Token::Integer(10))));
+    assert_eq!(lex.next(), Some(Ok(Token::Plus)));
+    assert_eq!(lex.rule().map(|rule| rule.index()), Some(5));
+    assert_eq!(lex.rule().map(|rule| rule.pattern()), Some("+"));
+    assert_eq!(lex.next(), Some(Ok(Token::Integer(16))));
+    assert_eq!(lex.rule().map(|rule| rule.pattern()), Some("0x[0-9a-f]+"));
+    assert_eq!(lex.next(), Some(Ok(Token::Plus)));
+    assert_eq!(lex.next(), Some(Ok(Token::Integer(7))));
+    assert_eq!(lex.next(), None);
+    assert_eq!(lex.extras, 2);
+}
+
+#[test]
+fn no_rule_on_error() {
+    let mut lex = Token::lexer("1 // comment\n?");
+
+    assert_eq!(lex.next(), Some(Ok(Token::Integer(1))));
+    assert!(lex.rule().is_some());
+    assert_eq!(lex.next(), Some(Err(())));
+    assert_eq!(lex.rule(), None);
+}
+
+mod callbacks {
+    use logos::{Lexer, Logos};
+
+    fn number(lex: &mut Lexer<Token>) -> Option<u64> {
+        lex.slice().parse().ok()
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(skip " +")]
+    enum Token {
+        #[regex("[0-9]+", number)]
+        Number(u64),
+
+        #[token("+")]
+        Plus,
+
+        #[regex("[a-z]+")]
+        Word,
+    }
+
+    #[test]
+    fn without_callbacks() {
+        let mut lex = Token::lexer("1 + 2 a 3 b c");
+        let mut patterns = Vec::new();
+        while let Some(token) = lex.next() {
+            assert!(token.is_ok());
+            patterns.push(lex.rule().map(|rule| rule.pattern()));
+        }
+
+        assert_eq!(
+            patterns,
+            [
+                Some("[0-9]+"),
+                Some("+"),
+                Some("[0-9]+"),
+                Some("[a-z]+"),
+                Some("[0-9]+"),
+                Some("[a-z]+"),
+                Some("[a-z]+"),
+            ]
+        );
+    }
+}
+
+mod bytes {
+    use logos::Logos;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(utf8 = false)]
+    enum Token {
+        #[token(b"\xFFa")]
+        #[regex(b"[\x00-\x7F]+")]
+        Bytes,
+    }
+
+    #[test]
+    fn escaped_pattern() {
+        let mut lex = Token::lexer(b"\xFFa");
+
+        assert_eq!(lex.next(), Some(Ok(Token::Bytes)));
+        assert_eq!(lex.rule().map(|rule| rule.pattern()), Some(r"\xffa"));
+        assert_eq!(lex.rule().map(|rule| rule.index()), Some(0));
+    }
+}
@@ -0,0 +1,13 @@
+use logos::Logos;
+
+#[derive(Logos)]
+#[logos(const_fn)]
+enum Token<'s> {
+    #[token("a", |_| true)]
+    A,
+
+    #[regex("[0-9]+")]
+    Number(&'s str),
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use logos::Logos;
+
+struct Interner;
+
+#[derive(Logos)]
+#[logos(extras = &'e mut Interner)]
+enum Token<'s> {
+    #[regex("[a-z]+")]
+    Ident(&'s str),
+}
+
+fn main() {}
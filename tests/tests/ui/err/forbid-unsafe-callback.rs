@@ -0,0 +1,10 @@
+use logos::Logos;
+
+#[derive(Logos)]
+#[logos(forbid_unsafe)]
+enum Token {
+    #[token("a", |lex| unsafe { lex.slice().get_unchecked(0..1).len() })]
+    A(usize),
+}
+
+fn main() {}
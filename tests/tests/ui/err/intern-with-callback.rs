@@ -0,0 +1,13 @@
+use logos::Logos;
+
+fn len(lex: &mut logos::Lexer<Token>) -> usize {
+    lex.slice().len()
+}
+
+#[derive(Logos)]
+enum Token {
+    #[regex("[a-z]+", len, intern)]
+    Ident(usize),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use logos::Logos;
+
+#[derive(Logos)]
+#[logos(max_token_len = 0)]
+enum Token {
+    #[regex("[a-z]+", max_len = "long")]
+    Word,
+}
+
+fn main() {}
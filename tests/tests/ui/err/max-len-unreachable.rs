@@ -0,0 +1,16 @@
+use logos::Logos;
+
+#[derive(Logos)]
+#[logos(max_token_len = 4)]
+enum Token {
+    #[token("continue")]
+    Continue,
+
+    #[regex("[a-z]+[0-9]{4}")]
+    Tagged,
+
+    #[regex("[a-z]+")]
+    Letters,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use logos::Logos;
+
+#[derive(Logos)]
+#[logos(owned = TokenOwned)]
+enum Token<'a> {
+    #[regex("[a-z]+", |lex| vec![lex.slice()])]
+    Words(Vec<&'a str>),
+}
+
+fn main() {}
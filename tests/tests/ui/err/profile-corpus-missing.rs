@@ -0,0 +1,10 @@
+use logos::Logos;
+
+#[derive(Logos)]
+#[logos(profile_corpus = "tests/data/missing.txt")]
+enum Token {
+    #[token("a")]
+    A,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use logos::Logos;
+
+#[derive(Logos)]
+#[logos(codegen = "recursive_descent")]
+enum Token {
+    #[token("a")]
+    A,
+}
+
+fn main() {}
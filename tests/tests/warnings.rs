@@ -0,0 +1,153 @@
+use logos::{Lexer, Logos};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Warning {
+    DeprecatedEscape,
+    Clamped,
+    Tab,
+}
+
+fn clamp(lex: &mut Lexer<Token>) -> u8 {
+    match lex.slice().parse() {
+        Ok(n) => n,
+        Err(_) => {
+            lex.warn(Warning::Clamped);
+            u8::MAX
+        }
+    }
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(skip " +")]
+#[logos(warning = Warning)]
+enum Token {
+    #[regex("[0-9]+", clamp)]
+    Number(u8),
+
+    #[token(r"\e", |lex| lex.warn(Warning::DeprecatedEscape))]
+    Escape,
+
+    #[token("{", |lex| lex.sublex(|_| (), indent))]
+    Block(usize),
+}
+
+fn indent(lex: &mut Lexer<Indent>) -> usize {
+    let mut n = 0;
+
+    while let Some(Ok(token)) = lex.next() {
+        match token {
+            Indent::Space => n += 1,
+            Indent::Tab => {
+                lex.warn(Warning::Tab);
+                n += 1;
+            }
+            Indent::End => break,
+        }
+    }
+    n
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(warning = Warning)]
+enum Indent {
+    #[token(" ")]
+    Space,
+
+    #[token("\t")]
+    Tab,
+
+    #[token("}")]
+    End,
+}
+
+#[test]
+fn warnings_are_not_errors() {
+    let mut lex = Token::lexer(r"42 1000 \e 7");
+
+    assert_eq!(lex.next(), Some(Ok(Token::Number(42))));
+    assert_eq!(lex.warnings().count(), 0);
+
+    assert_eq!(lex.next(), Some(Ok(Token::Number(255))));
+    assert_eq!(lex.next(), Some(Ok(Token::Escape)));
+    assert_eq!(
+        lex.warnings().collect::<Vec<_>>(),
+        [
+            (&Warning::Clamped, 3..7),
+            (&Warning::DeprecatedEscape, 8..10)
+        ]
+    );
+
+    assert_eq!(lex.take_warnings().len(), 2);
+    assert_eq!(lex.warnings().count(), 0);
+
+    assert_eq!(lex.next(), Some(Ok(Token::Number(7))));
+    assert_eq!(lex.next(), None);
+    assert!(lex.take_warnings().is_empty());
+}
+
+#[test]
+fn sublex_warnings() {
+    let mut lex = Token::lexer("1000 { \t \t}");
+
+    assert_eq!(lex.next(), Some(Ok(Token::Number(255))));
+    assert_eq!(lex.next(), Some(Ok(Token::Block(4))));
+    assert_eq!(
+        lex.take_warnings(),
+        vec![
+            (Warning::Clamped, 0..4),
+            (Warning::Tab, 7..8),
+            (Warning::Tab, 9..10),
+        ]
+    );
+}
+
+#[test]
+fn morph_warnings() {
+    let mut lex = Indent::lexer("\t}");
+
+    assert_eq!(lex.next(), Some(Ok(Indent::Tab)));
+    lex.warn(Warning::Tab);
+
+    let mut lex = lex.morph::<Token>();
+
+    assert_eq!(lex.next(), Some(Err(())));
+    assert_eq!(lex.take_warnings(), vec![(Warning::Tab, 0..1)]);
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(warning = &'static str)]
+enum Other {
+    #[token("}", |lex| lex.warn("brace"))]
+    Brace,
+}
+
+#[test]
+fn morph_other_warning_type() {
+    let mut lex = Indent::lexer("\t}");
+
+    assert_eq!(lex.next(), Some(Ok(Indent::Tab)));
+    lex.warn(Warning::Tab);
+
+    // Warnings of another type can't be stored by the new lexer
+    let mut lex = lex.morph::<Other>();
+
+    assert_eq!(lex.next(), Some(Ok(Other::Brace)));
+    assert_eq!(lex.take_warnings(), vec![("brace", 1..2)]);
+}
+
+#[derive(Logos, Debug, PartialEq)]
+enum NoWarnings {
+    #[token("a")]
+    A,
+}
+
+#[test]
+fn morph_without_warnings() {
+    let mut lex = Token::lexer("1000");
+
+    assert_eq!(lex.next(), Some(Ok(Token::Number(255))));
+
+    let lex = lex.morph::<NoWarnings>().morph::<Token>();
+
+    assert_eq!(lex.warnings().count(), 0);
+}